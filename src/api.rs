@@ -0,0 +1,811 @@
+use crate::{request::*, response::*, ParclV3ApiClient, Result};
+use async_trait::async_trait;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, time::Duration};
+
+#[async_trait]
+pub trait ParclV3Api: Send + Sync {
+    async fn get_exchange(&self) -> Result<ExchangeInfo>;
+
+    async fn get_exchange_cached(&self) -> Result<ExchangeInfo>;
+
+    async fn get_exchange_accounting(&self) -> Result<ExchangeInfoAccounting>;
+
+    async fn get_suggested_keeper_tip(&self, amount: u64) -> Result<u64>;
+
+    async fn get_exponents(&self) -> Result<HashMap<String, i32>>;
+
+    async fn get_exponent(&self, key: &str) -> Result<Option<i32>>;
+
+    async fn get_market_identifiers(
+        &self,
+        response_kind: MarketIdentifiersResponseKind,
+        exchange_id: Option<ExchangeIdentifier>,
+    ) -> Result<MarketIdentifiersResponse>;
+
+    async fn get_market_ids(&self) -> Result<Vec<MarketId>>;
+
+    async fn get_market_ids_paged(&self, page: PageParams) -> Result<MarketIdsPage>;
+
+    async fn get_market_ids_map(&self) -> Result<HashMap<MarketId, Pubkey>>;
+
+    async fn get_market_addresses(&self) -> Result<Vec<Pubkey>>;
+
+    async fn get_margin_account(
+        &self,
+        margin_account_id: MarginAccountIdentifier,
+        owner: Option<Pubkey>,
+    ) -> Result<MarginAccountInfo>;
+
+    async fn get_margin_account_opt(
+        &self,
+        margin_account_id: MarginAccountIdentifier,
+        owner: Option<Pubkey>,
+    ) -> Result<Option<MarginAccountInfo>>;
+
+    async fn get_margin_account_from_id(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountId,
+    ) -> Result<MarginAccountInfo>;
+
+    async fn get_margin_account_opt_from_id(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountId,
+    ) -> Result<Option<MarginAccountInfo>>;
+
+    async fn get_margin_account_from_address(&self, address: Pubkey) -> Result<MarginAccountInfo>;
+
+    async fn get_margin_accounts(
+        &self,
+        margin_accounts: &[Pubkey],
+    ) -> Result<Vec<Option<MarginAccountInfo>>>;
+
+    async fn get_margin_accounts_map(
+        &self,
+        margin_accounts: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, MarginAccountInfo>>;
+
+    async fn get_unhealthy_margin_accounts(&self) -> Result<Vec<Pubkey>>;
+
+    async fn get_market(&self, market_id: MarketIdentifier) -> Result<MarketInfo>;
+
+    async fn get_market_opt(&self, market_id: MarketIdentifier) -> Result<Option<MarketInfo>>;
+
+    async fn get_market_from_id(&self, market_id: MarketId) -> Result<MarketInfo>;
+
+    async fn get_market_cached(&self, market_id: MarketId, ttl: Duration) -> Result<MarketInfo>;
+
+    async fn get_market_from_address(&self, address: Pubkey) -> Result<MarketInfo>;
+
+    async fn get_markets(&self, market_ids: &[MarketIdentifier]) -> Result<Vec<MarketInfo>>;
+
+    async fn try_get_markets(
+        &self,
+        market_ids: &[MarketIdentifier],
+    ) -> Result<Vec<Result<MarketInfo>>>;
+
+    async fn get_markets_from_addresses(&self, addresses: &[Pubkey]) -> Result<Vec<MarketInfo>>;
+
+    async fn get_markets_from_ids(&self, ids: &[MarketId]) -> Result<Vec<MarketInfo>>;
+
+    async fn get_active_markets_for_account(
+        &self,
+        account: &MarginAccountInfo,
+    ) -> Result<Vec<MarketInfo>>;
+
+    async fn get_markets_snapshot(&self) -> Result<MarketsSnapshot>;
+
+    async fn get_markets_by_status(&self, status: MarketStatus) -> Result<Vec<MarketInfo>>;
+
+    async fn get_create_margin_account_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: Option<MarginAccountId>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<CreateMarginAccountTransactionResponse>;
+
+    async fn get_create_margin_account_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: Option<MarginAccountId>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<CreateMarginAccountInstructionsResponse>;
+
+    async fn get_close_margin_account_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo>;
+
+    async fn get_close_margin_account_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo>;
+
+    async fn get_deposit_margin_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo>;
+
+    async fn get_deposit_margin_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo>;
+
+    async fn get_withdraw_margin_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        settlement_request_id: Option<SettlementRequestId>,
+        keeper_tip: Option<u64>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo>;
+
+    async fn get_withdraw_margin_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        settlement_request_id: Option<SettlementRequestId>,
+        keeper_tip: Option<u64>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo>;
+
+    async fn get_modify_position_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo>;
+
+    async fn get_modify_position_full(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<(TransactionInfo, Instructions)>;
+
+    async fn get_modify_position_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo>;
+
+    async fn get_set_position_size_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        target_size: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo>;
+
+    async fn get_set_position_size_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        target_size: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo>;
+
+    async fn get_close_position_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo>;
+
+    async fn get_close_position_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo>;
+
+    async fn get_close_all_positions_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<Vec<InstructionInfo>>;
+
+    async fn get_liquidate_transaction(
+        &self,
+        margin_account_to_liquidate: Pubkey,
+        liquidator: Pubkey,
+        liquidator_margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo>;
+
+    async fn get_liquidate_instructions(
+        &self,
+        margin_account_to_liquidate: Pubkey,
+        liquidator: Pubkey,
+        liquidator_margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo>;
+
+    async fn get_modify_position_quote(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+    ) -> Result<ModifyPositionQuote>;
+
+    async fn get_modify_position_quotes(
+        &self,
+        requests: &[ModifyPositionQuotePayload],
+    ) -> Result<Vec<ModifyPositionQuote>>;
+
+    async fn get_close_position_quote(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        slippage_setting: SlippageSetting,
+    ) -> Result<ModifyPositionQuote>;
+}
+
+#[async_trait]
+impl ParclV3Api for ParclV3ApiClient {
+    async fn get_exchange(&self) -> Result<ExchangeInfo> {
+        ParclV3ApiClient::get_exchange(self).await
+    }
+
+    async fn get_exchange_cached(&self) -> Result<ExchangeInfo> {
+        ParclV3ApiClient::get_exchange_cached(self).await
+    }
+
+    async fn get_exchange_accounting(&self) -> Result<ExchangeInfoAccounting> {
+        ParclV3ApiClient::get_exchange_accounting(self).await
+    }
+
+    async fn get_suggested_keeper_tip(&self, amount: u64) -> Result<u64> {
+        ParclV3ApiClient::get_suggested_keeper_tip(self, amount).await
+    }
+
+    async fn get_exponents(&self) -> Result<HashMap<String, i32>> {
+        ParclV3ApiClient::get_exponents(self).await
+    }
+
+    async fn get_exponent(&self, key: &str) -> Result<Option<i32>> {
+        ParclV3ApiClient::get_exponent(self, key).await
+    }
+
+    async fn get_market_identifiers(
+        &self,
+        response_kind: MarketIdentifiersResponseKind,
+        exchange_id: Option<ExchangeIdentifier>,
+    ) -> Result<MarketIdentifiersResponse> {
+        ParclV3ApiClient::get_market_identifiers(self, response_kind, exchange_id).await
+    }
+
+    async fn get_market_ids(&self) -> Result<Vec<MarketId>> {
+        ParclV3ApiClient::get_market_ids(self).await
+    }
+
+    async fn get_market_ids_paged(&self, page: PageParams) -> Result<MarketIdsPage> {
+        ParclV3ApiClient::get_market_ids_paged(self, page).await
+    }
+
+    async fn get_market_ids_map(&self) -> Result<HashMap<MarketId, Pubkey>> {
+        ParclV3ApiClient::get_market_ids_map(self).await
+    }
+
+    async fn get_market_addresses(&self) -> Result<Vec<Pubkey>> {
+        ParclV3ApiClient::get_market_addresses(self).await
+    }
+
+    async fn get_margin_account(
+        &self,
+        margin_account_id: MarginAccountIdentifier,
+        owner: Option<Pubkey>,
+    ) -> Result<MarginAccountInfo> {
+        ParclV3ApiClient::get_margin_account(self, margin_account_id, owner).await
+    }
+
+    async fn get_margin_account_opt(
+        &self,
+        margin_account_id: MarginAccountIdentifier,
+        owner: Option<Pubkey>,
+    ) -> Result<Option<MarginAccountInfo>> {
+        ParclV3ApiClient::get_margin_account_opt(self, margin_account_id, owner).await
+    }
+
+    async fn get_margin_account_from_id(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountId,
+    ) -> Result<MarginAccountInfo> {
+        ParclV3ApiClient::get_margin_account_from_id(self, owner, margin_account_id).await
+    }
+
+    async fn get_margin_account_opt_from_id(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountId,
+    ) -> Result<Option<MarginAccountInfo>> {
+        ParclV3ApiClient::get_margin_account_opt_from_id(self, owner, margin_account_id).await
+    }
+
+    async fn get_margin_account_from_address(&self, address: Pubkey) -> Result<MarginAccountInfo> {
+        ParclV3ApiClient::get_margin_account_from_address(self, address).await
+    }
+
+    async fn get_margin_accounts(
+        &self,
+        margin_accounts: &[Pubkey],
+    ) -> Result<Vec<Option<MarginAccountInfo>>> {
+        ParclV3ApiClient::get_margin_accounts(self, margin_accounts).await
+    }
+
+    async fn get_margin_accounts_map(
+        &self,
+        margin_accounts: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, MarginAccountInfo>> {
+        ParclV3ApiClient::get_margin_accounts_map(self, margin_accounts).await
+    }
+
+    async fn get_unhealthy_margin_accounts(&self) -> Result<Vec<Pubkey>> {
+        ParclV3ApiClient::get_unhealthy_margin_accounts(self).await
+    }
+
+    async fn get_market(&self, market_id: MarketIdentifier) -> Result<MarketInfo> {
+        ParclV3ApiClient::get_market(self, market_id).await
+    }
+
+    async fn get_market_opt(&self, market_id: MarketIdentifier) -> Result<Option<MarketInfo>> {
+        ParclV3ApiClient::get_market_opt(self, market_id).await
+    }
+
+    async fn get_market_from_id(&self, market_id: MarketId) -> Result<MarketInfo> {
+        ParclV3ApiClient::get_market_from_id(self, market_id).await
+    }
+
+    async fn get_market_cached(&self, market_id: MarketId, ttl: Duration) -> Result<MarketInfo> {
+        ParclV3ApiClient::get_market_cached(self, market_id, ttl).await
+    }
+
+    async fn get_market_from_address(&self, address: Pubkey) -> Result<MarketInfo> {
+        ParclV3ApiClient::get_market_from_address(self, address).await
+    }
+
+    async fn get_markets(&self, market_ids: &[MarketIdentifier]) -> Result<Vec<MarketInfo>> {
+        ParclV3ApiClient::get_markets(self, market_ids).await
+    }
+
+    async fn try_get_markets(
+        &self,
+        market_ids: &[MarketIdentifier],
+    ) -> Result<Vec<Result<MarketInfo>>> {
+        ParclV3ApiClient::try_get_markets(self, market_ids).await
+    }
+
+    async fn get_markets_from_addresses(&self, addresses: &[Pubkey]) -> Result<Vec<MarketInfo>> {
+        ParclV3ApiClient::get_markets_from_addresses(self, addresses).await
+    }
+
+    async fn get_markets_from_ids(&self, ids: &[MarketId]) -> Result<Vec<MarketInfo>> {
+        ParclV3ApiClient::get_markets_from_ids(self, ids).await
+    }
+
+    async fn get_active_markets_for_account(
+        &self,
+        account: &MarginAccountInfo,
+    ) -> Result<Vec<MarketInfo>> {
+        ParclV3ApiClient::get_active_markets_for_account(self, account).await
+    }
+
+    async fn get_markets_snapshot(&self) -> Result<MarketsSnapshot> {
+        ParclV3ApiClient::get_markets_snapshot(self).await
+    }
+
+    async fn get_markets_by_status(&self, status: MarketStatus) -> Result<Vec<MarketInfo>> {
+        ParclV3ApiClient::get_markets_by_status(self, status).await
+    }
+
+    async fn get_create_margin_account_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: Option<MarginAccountId>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<CreateMarginAccountTransactionResponse> {
+        ParclV3ApiClient::get_create_margin_account_transaction(
+            self,
+            owner,
+            margin_account_id,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_create_margin_account_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: Option<MarginAccountId>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<CreateMarginAccountInstructionsResponse> {
+        ParclV3ApiClient::get_create_margin_account_instructions(
+            self,
+            owner,
+            margin_account_id,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_close_margin_account_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        ParclV3ApiClient::get_close_margin_account_transaction(
+            self,
+            owner,
+            margin_account_id,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_close_margin_account_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        ParclV3ApiClient::get_close_margin_account_instructions(
+            self,
+            owner,
+            margin_account_id,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_deposit_margin_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        ParclV3ApiClient::get_deposit_margin_transaction(
+            self,
+            owner,
+            margin_account_id,
+            margin,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_deposit_margin_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        ParclV3ApiClient::get_deposit_margin_instructions(
+            self,
+            owner,
+            margin_account_id,
+            margin,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_withdraw_margin_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        settlement_request_id: Option<SettlementRequestId>,
+        keeper_tip: Option<u64>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        ParclV3ApiClient::get_withdraw_margin_transaction(
+            self,
+            owner,
+            margin_account_id,
+            margin,
+            settlement_request_id,
+            keeper_tip,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_withdraw_margin_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        settlement_request_id: Option<SettlementRequestId>,
+        keeper_tip: Option<u64>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        ParclV3ApiClient::get_withdraw_margin_instructions(
+            self,
+            owner,
+            margin_account_id,
+            margin,
+            settlement_request_id,
+            keeper_tip,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_modify_position_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        ParclV3ApiClient::get_modify_position_transaction(
+            self,
+            owner,
+            margin_account_id,
+            market_id,
+            size_delta,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_modify_position_full(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<(TransactionInfo, Instructions)> {
+        ParclV3ApiClient::get_modify_position_full(
+            self,
+            owner,
+            margin_account_id,
+            market_id,
+            size_delta,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_modify_position_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        ParclV3ApiClient::get_modify_position_instructions(
+            self,
+            owner,
+            margin_account_id,
+            market_id,
+            size_delta,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_set_position_size_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        target_size: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        ParclV3ApiClient::get_set_position_size_transaction(
+            self,
+            owner,
+            margin_account_id,
+            market_id,
+            target_size,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_set_position_size_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        target_size: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        ParclV3ApiClient::get_set_position_size_instructions(
+            self,
+            owner,
+            margin_account_id,
+            market_id,
+            target_size,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_close_position_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        ParclV3ApiClient::get_close_position_transaction(
+            self,
+            owner,
+            margin_account_id,
+            market_id,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_close_position_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        ParclV3ApiClient::get_close_position_instructions(
+            self,
+            owner,
+            margin_account_id,
+            market_id,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_close_all_positions_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<Vec<InstructionInfo>> {
+        ParclV3ApiClient::get_close_all_positions_instructions(
+            self,
+            owner,
+            margin_account_id,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_liquidate_transaction(
+        &self,
+        margin_account_to_liquidate: Pubkey,
+        liquidator: Pubkey,
+        liquidator_margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        ParclV3ApiClient::get_liquidate_transaction(
+            self,
+            margin_account_to_liquidate,
+            liquidator,
+            liquidator_margin_account_id,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_liquidate_instructions(
+        &self,
+        margin_account_to_liquidate: Pubkey,
+        liquidator: Pubkey,
+        liquidator_margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        ParclV3ApiClient::get_liquidate_instructions(
+            self,
+            margin_account_to_liquidate,
+            liquidator,
+            liquidator_margin_account_id,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    async fn get_modify_position_quote(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+    ) -> Result<ModifyPositionQuote> {
+        ParclV3ApiClient::get_modify_position_quote(
+            self,
+            owner,
+            margin_account_id,
+            market_id,
+            size_delta,
+            slippage_setting,
+        )
+        .await
+    }
+
+    async fn get_modify_position_quotes(
+        &self,
+        requests: &[ModifyPositionQuotePayload],
+    ) -> Result<Vec<ModifyPositionQuote>> {
+        ParclV3ApiClient::get_modify_position_quotes(self, requests).await
+    }
+
+    async fn get_close_position_quote(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        slippage_setting: SlippageSetting,
+    ) -> Result<ModifyPositionQuote> {
+        ParclV3ApiClient::get_close_position_quote(
+            self,
+            owner,
+            margin_account_id,
+            market_id,
+            slippage_setting,
+        )
+        .await
+    }
+}
@@ -0,0 +1,1457 @@
+use crate::{
+    constants::*,
+    request::*,
+    response::*,
+    utils::{
+        buffered_try_flatten, get_exchange_address_from_identifier, get_margin_account_address,
+    },
+    ParclV3ApiClientError, Result,
+};
+
+use dashmap::DashMap;
+use futures::{stream, Stream};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, ACCEPT, ETAG, IF_NONE_MATCH},
+    Client, Request, RequestBuilder, Response, StatusCode,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+use tokio::sync::OnceCell;
+#[cfg(feature = "cancellation")]
+use tokio_util::sync::CancellationToken;
+
+// Concurrent misses for a market wait on the same cell instead of each fetching it.
+type CachedMarket = Arc<OnceCell<(Instant, MarketInfo)>>;
+
+#[derive(Clone)]
+pub struct ParclV3ApiClient {
+    client: Client,
+    base_url: String,
+    exchange_id: ExchangeIdentifier,
+    priority_fee_percentile: Option<u16>,
+    max_priority_fee_lamports: Option<u64>,
+    idempotency_key: Option<String>,
+    api_version: Option<String>,
+    dry_run: Option<Arc<Mutex<Option<Request>>>>,
+    max_concurrent_requests: usize,
+    retry: RetryConfig,
+    market_cache: Arc<DashMap<MarketId, CachedMarket>>,
+    exchange_cache: Arc<DashMap<String, (String, ExchangeInfo)>>,
+}
+
+impl Default for ParclV3ApiClient {
+    fn default() -> Self {
+        Self::new(ParclV3ApiClientConfig::default())
+    }
+}
+
+#[derive(Clone)]
+pub struct ParclV3ApiClientConfig {
+    pub base_url: String,
+    pub exchange_id: Option<ExchangeIdentifier>,
+    /// Default for transaction and instruction methods. Their priority_fee_percentile argument
+    /// overrides it when Some.
+    pub priority_fee_percentile: Option<u16>,
+    /// Rejects transactions and instructions whose required compute lamports exceed this cap.
+    pub max_priority_fee_lamports: Option<u64>,
+    pub headers: Option<HeaderMap>,
+    pub accept_compression: bool,
+    pub user_agent: Option<String>,
+    /// Caps in-flight requests for batch methods. Defaults to DEFAULT_MAX_CONCURRENT_REQUESTS (8).
+    pub max_concurrent_requests: usize,
+    /// Skips TLS certificate verification. Only for local development against self-signed servers.
+    pub danger_accept_invalid_certs: bool,
+    /// Retries connect and timeout errors, 429s, and 5xxs. Disabled by default.
+    pub retry: RetryConfig,
+    /// Pins the api version via the Accept header. The server responds 406 if it can't serve it.
+    /// Replaces any Accept header set in headers.
+    pub api_version: Option<String>,
+}
+
+impl Default for ParclV3ApiClientConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_V3_API_URL.to_string(),
+            exchange_id: None,
+            priority_fee_percentile: None,
+            max_priority_fee_lamports: None,
+            headers: None,
+            accept_compression: true,
+            user_agent: None,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            danger_accept_invalid_certs: false,
+            retry: RetryConfig::default(),
+            api_version: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    /// Doubles after each attempt up to max_backoff.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Stops retrying once the next attempt would start after this much time since the first one.
+    pub max_elapsed: Option<Duration>,
+    /// Sleeps a random duration between zero and the backoff instead of the full backoff.
+    pub jitter: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            max_elapsed: None,
+            jitter: false,
+        }
+    }
+}
+
+impl RetryConfig {
+    fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        if self.jitter {
+            backoff.mul_f64(rand::random::<f64>())
+        } else {
+            backoff
+        }
+    }
+}
+
+impl ParclV3ApiClient {
+    /// Panics if the config is invalid, e.g. an api_version that isn't a valid header value. Use
+    /// try_new to handle that as an error.
+    pub fn new(config: ParclV3ApiClientConfig) -> Self {
+        Self::try_new(config).expect("Invalid client config")
+    }
+
+    pub fn try_new(config: ParclV3ApiClientConfig) -> Result<Self> {
+        let mut client_builder = Client::builder()
+            .user_agent(
+                config
+                    .user_agent
+                    .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+            )
+            .gzip(config.accept_compression)
+            .brotli(config.accept_compression)
+            .danger_accept_invalid_certs(config.danger_accept_invalid_certs);
+        let mut headers = config.headers.unwrap_or_default();
+        if let Some(api_version) = &config.api_version {
+            let accept =
+                HeaderValue::from_str(&format!("{API_VERSION_MEDIA_TYPE}; version={api_version}"))
+                    .map_err(|_| {
+                        ParclV3ApiClientError::InvalidInput(format!(
+                            "api_version {api_version:?} is not a valid header value"
+                        ))
+                    })?;
+            headers.insert(ACCEPT, accept);
+        }
+        if !headers.is_empty() {
+            client_builder = client_builder.default_headers(headers);
+        }
+        // rustls takes precedence when both tls features are enabled.
+        #[cfg(feature = "rustls-tls")]
+        {
+            client_builder = client_builder.use_rustls_tls();
+        }
+        Ok(Self {
+            client: client_builder.build()?,
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            exchange_id: config.exchange_id.unwrap_or_default(),
+            priority_fee_percentile: config.priority_fee_percentile,
+            max_priority_fee_lamports: config.max_priority_fee_lamports,
+            idempotency_key: None,
+            api_version: config.api_version,
+            dry_run: None,
+            max_concurrent_requests: config.max_concurrent_requests.max(1),
+            retry: config.retry,
+            market_cache: Arc::new(DashMap::new()),
+            exchange_cache: Arc::new(DashMap::new()),
+        })
+    }
+
+    pub fn from_url(base_url: impl Into<String>) -> Self {
+        Self::new(ParclV3ApiClientConfig {
+            base_url: base_url.into(),
+            ..Default::default()
+        })
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn exchange_id(&self) -> ExchangeIdentifier {
+        self.exchange_id
+    }
+
+    pub fn priority_fee_percentile(&self) -> Option<u16> {
+        self.priority_fee_percentile
+    }
+
+    /// Sends idempotency_key as the Idempotency-Key header on transaction and instruction requests.
+    /// A key is single-use: use a fresh key per logical request. Methods that fan out into several
+    /// requests, like get_close_all_positions_instructions, suffix the key with the market id.
+    pub fn with_idempotency_key(&self, idempotency_key: impl Into<String>) -> Self {
+        Self {
+            idempotency_key: Some(idempotency_key.into()),
+            ..self.clone()
+        }
+    }
+
+    /// Runs call against a copy of this client that builds its first request instead of sending it,
+    /// and returns that request for inspection, e.g.
+    /// `client.dry_run(|client| async move { client.get_exchange().await })`. Errors raised before a
+    /// request is built, like input validation, are returned as is.
+    pub async fn dry_run<T, F, Fut>(&self, call: F) -> Result<Request>
+    where
+        F: FnOnce(ParclV3ApiClient) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let captured_request = Arc::new(Mutex::new(None));
+        let result = call(Self {
+            dry_run: Some(captured_request.clone()),
+            ..self.clone()
+        })
+        .await;
+        let request = captured_request
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        match (request, result) {
+            (Some(request), _) => Ok(request),
+            (None, Err(err)) => Err(err),
+            (None, Ok(_)) => Err(ParclV3ApiClientError::InvalidInput(
+                "dry run call did not build a request".to_string(),
+            )),
+        }
+    }
+
+    fn check_max_priority_fee(&self, lamport_requirements: LamportRequirements) -> Result<()> {
+        match self.max_priority_fee_lamports {
+            Some(max_priority_fee_lamports)
+                if lamport_requirements.priority_fee() > max_priority_fee_lamports =>
+            {
+                Err(ParclV3ApiClientError::PriorityFeeExceedsCap(
+                    lamport_requirements.priority_fee(),
+                    max_priority_fee_lamports,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn build_url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = self.send_with_retry(request).await?;
+        // Without a pinned version a 406 is an ordinary request error.
+        if self.api_version.is_some() && response.status() == StatusCode::NOT_ACCEPTABLE {
+            return Err(ParclV3ApiClientError::VersionMismatch(
+                response.text().await.unwrap_or_default(),
+            ));
+        }
+        Ok(response)
+    }
+
+    async fn send_with_retry(&self, request: RequestBuilder) -> Result<Response> {
+        let request = request.build()?;
+        if let Some(captured_request) = &self.dry_run {
+            tracing::debug!(method = %request.method(), url = %request.url(), "dry run");
+            captured_request
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_or_insert(request);
+            // The empty placeholder body fails deserialization, which ends the call.
+            return Ok(http::Response::new(Vec::<u8>::new()).into());
+        }
+        let start = Instant::now();
+        let mut attempt = 0;
+        loop {
+            let Some(retry_request) = request.try_clone() else {
+                return self.client.execute(request).await.map_err(Into::into);
+            };
+            let result = self.client.execute(retry_request).await;
+            let retryable = match &result {
+                Ok(response) => {
+                    response.status() == StatusCode::TOO_MANY_REQUESTS
+                        || response.status().is_server_error()
+                }
+                Err(err) => err.is_connect() || err.is_timeout(),
+            };
+            if !retryable || attempt >= self.retry.max_retries {
+                return result.map_err(Into::into);
+            }
+            let backoff = self.retry.backoff(attempt);
+            if let Some(max_elapsed) = self.retry.max_elapsed {
+                if start.elapsed() + backoff > max_elapsed {
+                    return result.map_err(Into::into);
+                }
+            }
+            tracing::debug!(url = %request.url(), attempt, ?backoff, "retrying request");
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    fn post_transaction_request(&self, path: &str) -> RequestBuilder {
+        let request = self.client.post(self.build_url(path));
+        match &self.idempotency_key {
+            Some(idempotency_key) => request.header(IDEMPOTENCY_KEY_HEADER, idempotency_key),
+            None => request,
+        }
+    }
+
+    pub async fn get_exchange(&self) -> Result<ExchangeInfo> {
+        let response = self
+            .send(
+                self.client
+                    .get(self.build_url(EXCHANGE_PATH))
+                    .query(&[("exchange_id", self.exchange_id.to_string())]),
+            )
+            .await?;
+        validate_and_deserialize_response::<ExchangeInfo>(response).await
+    }
+
+    /// Sends If-None-Match with the last seen ETag and returns the cached exchange on 304 Not Modified.
+    pub async fn get_exchange_cached(&self) -> Result<ExchangeInfo> {
+        let exchange_id = self.exchange_id.to_string();
+        let request = || {
+            self.client
+                .get(self.build_url(EXCHANGE_PATH))
+                .query(&[("exchange_id", exchange_id.as_str())])
+        };
+        let cached_etag = self
+            .exchange_cache
+            .get(&exchange_id)
+            .map(|entry| entry.value().0.clone());
+        let response = match cached_etag {
+            Some(etag) => self.send(request().header(IF_NONE_MATCH, etag)).await?,
+            None => self.send(request()).await?,
+        };
+        let response = if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = self.exchange_cache.get(&exchange_id) {
+                return Ok(entry.value().1.clone());
+            }
+            // Nothing cached to reuse, e.g. a 304 from an intermediate cache, so refetch it.
+            self.send(request()).await?
+        } else {
+            response
+        };
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string);
+        let exchange = validate_and_deserialize_response::<ExchangeInfo>(response).await?;
+        if let Some(etag) = etag {
+            self.exchange_cache
+                .insert(exchange_id, (etag, exchange.clone()));
+        }
+        Ok(exchange)
+    }
+
+    /// There is no separate accounting endpoint, so this reads it from the ETag cached exchange.
+    pub async fn get_exchange_accounting(&self) -> Result<ExchangeInfoAccounting> {
+        Ok(self.get_exchange_cached().await?.accounting)
+    }
+
+    pub async fn get_suggested_keeper_tip(&self, amount: u64) -> Result<u64> {
+        let exchange = self.get_exchange().await?;
+        Ok(exchange.settings.max_keeper_tip(amount))
+    }
+
+    pub async fn get_exponents(&self) -> Result<HashMap<String, i32>> {
+        let response = self
+            .send(
+                self.client
+                    .get(self.build_url(EXPONENTS_PATH))
+                    .query(&[("exchange_id", self.exchange_id.to_string())]),
+            )
+            .await?;
+        validate_and_deserialize_response::<HashMap<String, i32>>(response).await
+    }
+
+    pub async fn get_exponent(&self, key: &str) -> Result<Option<i32>> {
+        let exponents = self.get_exponents().await?;
+        Ok(exponents.get(key).copied())
+    }
+
+    pub async fn get_market_identifiers(
+        &self,
+        response_kind: MarketIdentifiersResponseKind,
+        exchange_id: Option<ExchangeIdentifier>,
+    ) -> Result<MarketIdentifiersResponse> {
+        let exchange_id = exchange_id.unwrap_or(self.exchange_id);
+        let response = self
+            .send(
+                self.client
+                    .get(self.build_url(MARKET_IDS_PATH))
+                    .query(&[("response_kind", response_kind)])
+                    .query(&[("exchange_id", exchange_id.to_string())]),
+            )
+            .await?;
+        let value = validate_and_deserialize_response::<serde_json::Value>(response).await?;
+        MarketIdentifiersResponse::from_json_value(response_kind, value)
+    }
+
+    pub async fn get_market_ids(&self) -> Result<Vec<MarketId>> {
+        let response = self
+            .get_market_identifiers(MarketIdentifiersResponseKind::Ids, None)
+            .await?;
+        match response {
+            MarketIdentifiersResponse::Ids(ids) => Ok(ids),
+            _ => Err(ParclV3ApiClientError::MarketIdsResponse(
+                MarketIdentifiersResponseKind::Ids,
+            )),
+        }
+    }
+
+    /// /market-ids is not paginated server-side, so this fetches every id and pages client-side.
+    /// A missing limit returns everything from offset onward.
+    pub async fn get_market_ids_paged(&self, page: PageParams) -> Result<MarketIdsPage> {
+        let market_ids = self.get_market_ids().await?;
+        Ok(MarketIdsPage::from_market_ids(market_ids, page))
+    }
+
+    pub async fn get_market_ids_map(&self) -> Result<HashMap<MarketId, Pubkey>> {
+        let response = self
+            .get_market_identifiers(MarketIdentifiersResponseKind::Map, None)
+            .await?;
+        match response {
+            MarketIdentifiersResponse::Map(map) => Ok(map),
+            _ => Err(ParclV3ApiClientError::MarketIdsResponse(
+                MarketIdentifiersResponseKind::Map,
+            )),
+        }
+    }
+
+    pub async fn get_market_addresses(&self) -> Result<Vec<Pubkey>> {
+        let response = self
+            .get_market_identifiers(MarketIdentifiersResponseKind::Addresses, None)
+            .await?;
+        match response {
+            MarketIdentifiersResponse::Addresses(addresses) => Ok(addresses),
+            _ => Err(ParclV3ApiClientError::MarketIdsResponse(
+                MarketIdentifiersResponseKind::Addresses,
+            )),
+        }
+    }
+
+    pub async fn get_margin_account(
+        &self,
+        margin_account_id: MarginAccountIdentifier,
+        owner: Option<Pubkey>,
+    ) -> Result<MarginAccountInfo> {
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        if let Some(owner) = owner {
+            validate_pubkey("owner", owner)?;
+        }
+        let response = self
+            .send(
+                self.client
+                    .get(self.build_url(MARGIN_ACCOUNT_PATH))
+                    .query(&[("margin_account_id", margin_account_id.to_string())])
+                    .query(&[("owner", owner.map(|owner| owner.to_string()))])
+                    .query(&[("exchange_id", self.exchange_id.to_string())]),
+            )
+            .await?;
+        validate_and_deserialize_response::<MarginAccountInfo>(response).await
+    }
+
+    pub async fn get_margin_account_opt(
+        &self,
+        margin_account_id: MarginAccountIdentifier,
+        owner: Option<Pubkey>,
+    ) -> Result<Option<MarginAccountInfo>> {
+        not_found_as_none(self.get_margin_account(margin_account_id, owner).await)
+    }
+
+    pub async fn get_margin_account_from_id(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountId,
+    ) -> Result<MarginAccountInfo> {
+        self.get_margin_account(MarginAccountIdentifier::Id(margin_account_id), Some(owner))
+            .await
+    }
+
+    /// Derives the margin account address locally and fetches it by address, returning None if it
+    /// doesn't exist.
+    pub async fn get_margin_account_opt_from_id(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountId,
+    ) -> Result<Option<MarginAccountInfo>> {
+        validate_pubkey("owner", owner)?;
+        let address = get_margin_account_address(
+            &get_exchange_address_from_identifier(self.exchange_id),
+            &owner,
+            margin_account_id,
+        );
+        not_found_as_none(self.get_margin_account_from_address(address).await)
+    }
+
+    pub async fn get_margin_account_from_address(
+        &self,
+        address: Pubkey,
+    ) -> Result<MarginAccountInfo> {
+        self.get_margin_account(MarginAccountIdentifier::Address(address), None)
+            .await
+    }
+
+    pub async fn get_margin_accounts(
+        &self,
+        margin_accounts: &[Pubkey],
+    ) -> Result<Vec<Option<MarginAccountInfo>>> {
+        for margin_account in margin_accounts {
+            validate_pubkey("margin_account", *margin_account)?;
+        }
+        buffered_try_flatten(
+            margin_accounts
+                .chunks(MAX_BATCH_CHUNK_SIZE)
+                .map(|chunk| self.get_margin_accounts_chunk(chunk))
+                .collect::<Vec<_>>(),
+            self.max_concurrent_requests,
+        )
+        .await
+    }
+
+    /// Same as get_margin_accounts but keyed by address, with missing accounts omitted.
+    pub async fn get_margin_accounts_map(
+        &self,
+        margin_accounts: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, MarginAccountInfo>> {
+        Ok(self
+            .get_margin_accounts(margin_accounts)
+            .await?
+            .into_iter()
+            .flatten()
+            .map(|margin_account| (margin_account.address, margin_account))
+            .collect())
+    }
+
+    async fn get_margin_accounts_chunk(
+        &self,
+        margin_accounts: &[Pubkey],
+    ) -> Result<Vec<Option<MarginAccountInfo>>> {
+        let response = self
+            .send(self.client.post(self.build_url(MARGIN_ACCOUNTS_PATH)).json(
+                &MarginAccountsPayload {
+                    margin_accounts: margin_accounts.to_vec(),
+                    exchange_id: Some(self.exchange_id),
+                },
+            ))
+            .await?;
+        validate_and_deserialize_response::<Vec<Option<MarginAccountInfo>>>(response).await
+    }
+
+    pub async fn get_unhealthy_margin_accounts(&self) -> Result<Vec<Pubkey>> {
+        let response = self
+            .send(
+                self.client
+                    .get(self.build_url(UNHEALTHY_MARGIN_ACCOUNTS_PATH))
+                    .query(&[("exchange_id", self.exchange_id.to_string())]),
+            )
+            .await?;
+        let unhealthy_margin_accounts =
+            validate_and_deserialize_response::<Vec<String>>(response).await?;
+        Ok(unhealthy_margin_accounts
+            .into_iter()
+            .flat_map(|s| Pubkey::from_str(&s).ok())
+            .collect::<Vec<Pubkey>>())
+    }
+
+    /// Polls get_unhealthy_margin_accounts every interval and yields accounts that were not unhealthy
+    /// on the previous poll. Polls with no newly unhealthy accounts are skipped.
+    pub fn unhealthy_account_stream(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Vec<Pubkey>>> + '_ {
+        stream::unfold(
+            (HashSet::<Pubkey>::new(), true),
+            move |(mut previous, mut first_poll)| async move {
+                loop {
+                    if !first_poll {
+                        tokio::time::sleep(interval).await;
+                    }
+                    first_poll = false;
+                    match self.get_unhealthy_margin_accounts().await {
+                        Ok(unhealthy_margin_accounts) => {
+                            let current = unhealthy_margin_accounts
+                                .into_iter()
+                                .collect::<HashSet<Pubkey>>();
+                            let newly_unhealthy = current
+                                .difference(&previous)
+                                .copied()
+                                .collect::<Vec<Pubkey>>();
+                            previous = current;
+                            if !newly_unhealthy.is_empty() {
+                                return Some((Ok(newly_unhealthy), (previous, first_poll)));
+                            }
+                        }
+                        Err(err) => return Some((Err(err), (previous, first_poll))),
+                    }
+                }
+            },
+        )
+    }
+
+    pub async fn get_market(&self, market_id: MarketIdentifier) -> Result<MarketInfo> {
+        validate_identifier("market_id", market_id.address())?;
+        let response = self
+            .send(
+                self.client
+                    .get(self.build_url(MARKET_PATH))
+                    .query(&[("market_id", market_id.to_string())])
+                    .query(&[("exchange_id", self.exchange_id.to_string())]),
+            )
+            .await?;
+        validate_and_deserialize_response::<MarketInfo>(response).await
+    }
+
+    pub async fn get_market_opt(&self, market_id: MarketIdentifier) -> Result<Option<MarketInfo>> {
+        not_found_as_none(self.get_market(market_id).await)
+    }
+
+    pub async fn get_market_from_id(&self, market_id: MarketId) -> Result<MarketInfo> {
+        self.get_market(MarketIdentifier::Id(market_id)).await
+    }
+
+    /// Returns the cached market if it was fetched less than ttl ago. Concurrent misses for the same
+    /// id share a single request.
+    pub async fn get_market_cached(
+        &self,
+        market_id: MarketId,
+        ttl: Duration,
+    ) -> Result<MarketInfo> {
+        let cell = {
+            let mut entry = self.market_cache.entry(market_id).or_default();
+            if matches!(entry.get(), Some((fetched_at, _)) if fetched_at.elapsed() >= ttl) {
+                *entry = Arc::default();
+            }
+            entry.clone()
+        };
+        let (_, market) = cell
+            .get_or_try_init(|| async {
+                let market = self.get_market_from_id(market_id).await?;
+                Ok::<_, ParclV3ApiClientError>((Instant::now(), market))
+            })
+            .await?;
+        Ok(market.clone())
+    }
+
+    pub async fn get_market_from_address(&self, address: Pubkey) -> Result<MarketInfo> {
+        self.get_market(MarketIdentifier::Address(address)).await
+    }
+
+    pub async fn get_markets(&self, market_ids: &[MarketIdentifier]) -> Result<Vec<MarketInfo>> {
+        for market_id in market_ids {
+            validate_identifier("market_id", market_id.address())?;
+        }
+        buffered_try_flatten(
+            market_ids
+                .chunks(MAX_BATCH_CHUNK_SIZE)
+                .map(|chunk| self.get_markets_chunk(chunk))
+                .collect::<Vec<_>>(),
+            self.max_concurrent_requests,
+        )
+        .await
+    }
+
+    /// Fetches each market individually so one bad id only fails its own entry. Results are in
+    /// input order.
+    pub async fn try_get_markets(
+        &self,
+        market_ids: &[MarketIdentifier],
+    ) -> Result<Vec<Result<MarketInfo>>> {
+        buffered_try_flatten(
+            market_ids
+                .iter()
+                .map(|market_id| async move { Ok(vec![self.get_market(*market_id).await]) })
+                .collect::<Vec<_>>(),
+            self.max_concurrent_requests,
+        )
+        .await
+    }
+
+    async fn get_markets_chunk(&self, market_ids: &[MarketIdentifier]) -> Result<Vec<MarketInfo>> {
+        let response = self
+            .send(
+                self.client
+                    .post(self.build_url(MARKETS_PATH))
+                    .json(&MarketsPayload {
+                        market_ids: market_ids.to_vec(),
+                        exchange_id: Some(self.exchange_id),
+                    }),
+            )
+            .await?;
+        validate_and_deserialize_response::<Vec<MarketInfo>>(response).await
+    }
+
+    pub async fn get_markets_from_addresses(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<MarketInfo>> {
+        let addresses = addresses
+            .iter()
+            .map(|address| MarketIdentifier::Address(*address))
+            .collect::<Vec<MarketIdentifier>>();
+        self.get_markets(&addresses).await
+    }
+
+    /// Results are sorted to match the order of `ids`, regardless of the order the server returns.
+    pub async fn get_markets_from_ids(&self, ids: &[MarketId]) -> Result<Vec<MarketInfo>> {
+        let mut positions = HashMap::with_capacity(ids.len());
+        for (position, id) in ids.iter().enumerate() {
+            positions.entry(*id).or_insert(position);
+        }
+        let identifiers = ids
+            .iter()
+            .map(|id| MarketIdentifier::Id(*id))
+            .collect::<Vec<MarketIdentifier>>();
+        let mut markets = self.get_markets(&identifiers).await?;
+        markets.sort_by_key(|market| positions.get(&market.id).copied().unwrap_or(usize::MAX));
+        Ok(markets)
+    }
+
+    pub async fn get_active_markets_for_account(
+        &self,
+        account: &MarginAccountInfo,
+    ) -> Result<Vec<MarketInfo>> {
+        self.get_markets_from_ids(&account.active_market_ids).await
+    }
+
+    #[cfg(feature = "cancellation")]
+    pub async fn get_all_markets_cancellable(
+        &self,
+        chunk_size: usize,
+        cancellation_token: &CancellationToken,
+    ) -> Result<Vec<MarketInfo>> {
+        let market_ids = self.get_market_ids().await?;
+        let mut markets = Vec::with_capacity(market_ids.len());
+        for chunk in market_ids.chunks(chunk_size.max(1)) {
+            if cancellation_token.is_cancelled() {
+                return Err(ParclV3ApiClientError::Cancelled);
+            }
+            markets.extend(self.get_markets_from_ids(chunk).await?);
+        }
+        Ok(markets)
+    }
+
+    pub async fn get_markets_snapshot(&self) -> Result<MarketsSnapshot> {
+        let market_ids = self.get_market_ids().await?;
+        let markets = self.get_markets_from_ids(&market_ids).await?;
+        Ok(MarketsSnapshot {
+            markets,
+            fetched_at: Instant::now(),
+        })
+    }
+
+    pub async fn get_markets_by_status(&self, status: MarketStatus) -> Result<Vec<MarketInfo>> {
+        let market_ids = self.get_market_ids().await?;
+        let markets = self.get_markets_from_ids(&market_ids).await?;
+        Ok(markets
+            .into_iter()
+            .filter(|market| market.market_status() == status)
+            .collect())
+    }
+
+    pub async fn get_create_margin_account_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: Option<MarginAccountId>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<CreateMarginAccountTransactionResponse> {
+        validate_pubkey("owner", owner)?;
+        let response = self
+            .send(
+                self.post_transaction_request(CREATE_MARGIN_ACCOUNT_TRANSACTION_PATH)
+                    .json(&CreateMarginAccountPayload {
+                        owner,
+                        margin_account_id,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let transaction_response =
+            validate_and_deserialize_response::<CreateMarginAccountTransactionResponse>(response)
+                .await?;
+        self.check_max_priority_fee(transaction_response.lamport_requirements())?;
+        Ok(transaction_response)
+    }
+
+    pub async fn get_create_margin_account_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: Option<MarginAccountId>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<CreateMarginAccountInstructionsResponse> {
+        validate_pubkey("owner", owner)?;
+        let response = self
+            .send(
+                self.post_transaction_request(CREATE_MARGIN_ACCOUNT_INSTRUCTIONS_PATH)
+                    .json(&CreateMarginAccountPayload {
+                        owner,
+                        margin_account_id,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let instructions_response: CreateMarginAccountInstructionsResponse =
+            validate_and_deserialize_response::<CreateMarginAccountInstructionsResponseInternal>(
+                response,
+            )
+            .await?
+            .into();
+        self.check_max_priority_fee(instructions_response.lamport_requirements())?;
+        Ok(instructions_response)
+    }
+
+    pub async fn get_close_margin_account_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let response = self
+            .send(
+                self.post_transaction_request(CLOSE_MARGIN_ACCOUNT_TRANSACTION_PATH)
+                    .json(&CloseMarginAccountPayload {
+                        owner,
+                        margin_account_id,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let transaction_info =
+            validate_and_deserialize_response::<TransactionInfo>(response).await?;
+        self.check_max_priority_fee(transaction_info.lamport_requirements())?;
+        Ok(transaction_info)
+    }
+
+    pub async fn get_close_margin_account_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let response = self
+            .send(
+                self.post_transaction_request(CLOSE_MARGIN_ACCOUNT_INSTRUCTIONS_PATH)
+                    .json(&CloseMarginAccountPayload {
+                        owner,
+                        margin_account_id,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let instruction_info: InstructionInfo =
+            validate_and_deserialize_response::<InstructionInfoInternal>(response)
+                .await?
+                .into();
+        self.check_max_priority_fee(instruction_info.lamport_requirements())?;
+        Ok(instruction_info)
+    }
+
+    pub async fn get_deposit_margin_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let response = self
+            .send(
+                self.post_transaction_request(DEPOSIT_MARGIN_TRANSACTION_PATH)
+                    .json(&DepositMarginPayload {
+                        owner,
+                        margin_account_id,
+                        margin,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let transaction_info =
+            validate_and_deserialize_response::<TransactionInfo>(response).await?;
+        self.check_max_priority_fee(transaction_info.lamport_requirements())?;
+        Ok(transaction_info)
+    }
+
+    pub async fn get_deposit_margin_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let response = self
+            .send(
+                self.post_transaction_request(DEPOSIT_MARGIN_INSTRUCTIONS_PATH)
+                    .json(&DepositMarginPayload {
+                        owner,
+                        margin_account_id,
+                        margin,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let instruction_info: InstructionInfo =
+            validate_and_deserialize_response::<InstructionInfoInternal>(response)
+                .await?
+                .into();
+        self.check_max_priority_fee(instruction_info.lamport_requirements())?;
+        Ok(instruction_info)
+    }
+
+    pub async fn get_withdraw_margin_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        settlement_request_id: Option<SettlementRequestId>,
+        keeper_tip: Option<u64>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let response = self
+            .send(
+                self.post_transaction_request(WITHDRAW_MARGIN_TRANSACTION_PATH)
+                    .json(&WithdrawMarginPayload {
+                        owner,
+                        margin_account_id,
+                        margin,
+                        settlement_request_id,
+                        keeper_tip,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let transaction_info =
+            validate_and_deserialize_response::<TransactionInfo>(response).await?;
+        self.check_max_priority_fee(transaction_info.lamport_requirements())?;
+        Ok(transaction_info)
+    }
+
+    pub async fn get_withdraw_margin_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        margin: u64,
+        settlement_request_id: Option<SettlementRequestId>,
+        keeper_tip: Option<u64>,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let response = self
+            .send(
+                self.post_transaction_request(WITHDRAW_MARGIN_INSTRUCTIONS_PATH)
+                    .json(&WithdrawMarginPayload {
+                        owner,
+                        margin_account_id,
+                        margin,
+                        settlement_request_id,
+                        keeper_tip,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let instruction_info: InstructionInfo =
+            validate_and_deserialize_response::<InstructionInfoInternal>(response)
+                .await?
+                .into();
+        self.check_max_priority_fee(instruction_info.lamport_requirements())?;
+        Ok(instruction_info)
+    }
+
+    pub async fn get_modify_position_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let response = self
+            .send(
+                self.post_transaction_request(MODIFY_POSITION_TRANSACTION_PATH)
+                    .json(&ModifyPositionPayload::new_for_client(
+                        self,
+                        owner,
+                        margin_account_id,
+                        market_id,
+                        size_delta,
+                        slippage_setting,
+                        priority_fee_percentile,
+                    )),
+            )
+            .await?;
+        let transaction_info =
+            validate_and_deserialize_response::<TransactionInfo>(response).await?;
+        self.check_max_priority_fee(transaction_info.lamport_requirements())?;
+        Ok(transaction_info)
+    }
+
+    /// Returns the server-built transaction along with its instructions decoded locally.
+    pub async fn get_modify_position_full(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<(TransactionInfo, Instructions)> {
+        let transaction_info = self
+            .get_modify_position_transaction(
+                owner,
+                margin_account_id,
+                market_id,
+                size_delta,
+                slippage_setting,
+                priority_fee_percentile,
+            )
+            .await?;
+        let instructions = transaction_info.decode_instructions()?;
+        Ok((transaction_info, instructions))
+    }
+
+    pub async fn get_modify_position_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let response = self
+            .send(
+                self.post_transaction_request(MODIFY_POSITION_INSTRUCTIONS_PATH)
+                    .json(&ModifyPositionPayload::new_for_client(
+                        self,
+                        owner,
+                        margin_account_id,
+                        market_id,
+                        size_delta,
+                        slippage_setting,
+                        priority_fee_percentile,
+                    )),
+            )
+            .await?;
+        let instruction_info: InstructionInfo =
+            validate_and_deserialize_response::<InstructionInfoInternal>(response)
+                .await?
+                .into();
+        self.check_max_priority_fee(instruction_info.lamport_requirements())?;
+        Ok(instruction_info)
+    }
+
+    async fn get_size_delta_to_target(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        target_size: i128,
+    ) -> Result<i128> {
+        let margin_account = self
+            .get_margin_account(margin_account_id, Some(owner))
+            .await?;
+        Ok(target_size - margin_account.position_size(market_id))
+    }
+
+    pub async fn get_set_position_size_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        target_size: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        validate_pubkey("owner", owner)?;
+        let size_delta = self
+            .get_size_delta_to_target(owner, margin_account_id, market_id, target_size)
+            .await?;
+        self.get_modify_position_transaction(
+            owner,
+            margin_account_id,
+            market_id,
+            size_delta,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    pub async fn get_set_position_size_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        target_size: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        validate_pubkey("owner", owner)?;
+        let size_delta = self
+            .get_size_delta_to_target(owner, margin_account_id, market_id, target_size)
+            .await?;
+        self.get_modify_position_instructions(
+            owner,
+            margin_account_id,
+            market_id,
+            size_delta,
+            slippage_setting,
+            priority_fee_percentile,
+        )
+        .await
+    }
+
+    pub async fn get_close_position_transaction(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let (maybe_acceptable_price, maybe_slippage_tolerance_bps) =
+            slippage_setting.as_request_fields();
+        let response = self
+            .send(
+                self.post_transaction_request(CLOSE_POSITION_TRANSACTION_PATH)
+                    .json(&ClosePositionPayload {
+                        owner,
+                        margin_account_id,
+                        market_id,
+                        acceptable_price: maybe_acceptable_price,
+                        slippage_tolerance_bps: maybe_slippage_tolerance_bps,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let transaction_info =
+            validate_and_deserialize_response::<TransactionInfo>(response).await?;
+        self.check_max_priority_fee(transaction_info.lamport_requirements())?;
+        Ok(transaction_info)
+    }
+
+    pub async fn get_close_position_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let (maybe_acceptable_price, maybe_slippage_tolerance_bps) =
+            slippage_setting.as_request_fields();
+        let response = self
+            .send(
+                self.post_transaction_request(CLOSE_POSITION_INSTRUCTIONS_PATH)
+                    .json(&ClosePositionPayload {
+                        owner,
+                        margin_account_id,
+                        market_id,
+                        acceptable_price: maybe_acceptable_price,
+                        slippage_tolerance_bps: maybe_slippage_tolerance_bps,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let instruction_info: InstructionInfo =
+            validate_and_deserialize_response::<InstructionInfoInternal>(response)
+                .await?
+                .into();
+        self.check_max_priority_fee(instruction_info.lamport_requirements())?;
+        Ok(instruction_info)
+    }
+
+    pub async fn get_close_all_positions_instructions(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<Vec<InstructionInfo>> {
+        validate_pubkey("owner", owner)?;
+        let margin_account = self
+            .get_margin_account(margin_account_id, Some(owner))
+            .await?;
+        buffered_try_flatten(
+            margin_account
+                .active_market_ids
+                .iter()
+                .map(|market_id| async move {
+                    let keyed_client = self.idempotency_key.as_ref().map(|idempotency_key| {
+                        self.with_idempotency_key(format!("{idempotency_key}-{market_id}"))
+                    });
+                    keyed_client
+                        .as_ref()
+                        .unwrap_or(self)
+                        .get_close_position_instructions(
+                            owner,
+                            margin_account_id,
+                            *market_id,
+                            slippage_setting,
+                            priority_fee_percentile,
+                        )
+                        .await
+                        .map(|ixs| vec![ixs])
+                })
+                .collect::<Vec<_>>(),
+            self.max_concurrent_requests,
+        )
+        .await
+    }
+
+    pub async fn get_liquidate_transaction(
+        &self,
+        margin_account_to_liquidate: Pubkey,
+        liquidator: Pubkey,
+        liquidator_margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<TransactionInfo> {
+        validate_pubkey("margin_account_to_liquidate", margin_account_to_liquidate)?;
+        validate_pubkey("liquidator", liquidator)?;
+        validate_identifier(
+            "liquidator_margin_account_id",
+            liquidator_margin_account_id.address(),
+        )?;
+        let response = self
+            .send(
+                self.post_transaction_request(LIQUIDATE_TRANSACTION_PATH)
+                    .json(&LiquidatePayload {
+                        margin_account_to_liquidate,
+                        liquidator,
+                        liquidator_margin_account_id,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let transaction_info =
+            validate_and_deserialize_response::<TransactionInfo>(response).await?;
+        self.check_max_priority_fee(transaction_info.lamport_requirements())?;
+        Ok(transaction_info)
+    }
+
+    pub async fn get_liquidate_instructions(
+        &self,
+        margin_account_to_liquidate: Pubkey,
+        liquidator: Pubkey,
+        liquidator_margin_account_id: MarginAccountIdentifier,
+        priority_fee_percentile: Option<u16>,
+    ) -> Result<InstructionInfo> {
+        validate_pubkey("margin_account_to_liquidate", margin_account_to_liquidate)?;
+        validate_pubkey("liquidator", liquidator)?;
+        validate_identifier(
+            "liquidator_margin_account_id",
+            liquidator_margin_account_id.address(),
+        )?;
+        let response = self
+            .send(
+                self.post_transaction_request(LIQUIDATE_INSTRUCTIONS_PATH)
+                    .json(&LiquidatePayload {
+                        margin_account_to_liquidate,
+                        liquidator,
+                        liquidator_margin_account_id,
+                        exchange_id: Some(self.exchange_id),
+                        priority_fee_percentile: priority_fee_percentile
+                            .or(self.priority_fee_percentile),
+                    }),
+            )
+            .await?;
+        let instruction_info: InstructionInfo =
+            validate_and_deserialize_response::<InstructionInfoInternal>(response)
+                .await?
+                .into();
+        self.check_max_priority_fee(instruction_info.lamport_requirements())?;
+        Ok(instruction_info)
+    }
+
+    pub async fn get_modify_position_quote(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+    ) -> Result<ModifyPositionQuote> {
+        validate_pubkey("owner", owner)?;
+        validate_identifier("margin_account_id", margin_account_id.address())?;
+        let (maybe_acceptable_price, maybe_slippage_tolerance_bps) =
+            slippage_setting.as_request_fields();
+        self.get_modify_position_quote_from_payload(ModifyPositionQuotePayload {
+            owner,
+            margin_account_id,
+            market_id,
+            size_delta,
+            acceptable_price: maybe_acceptable_price,
+            slippage_tolerance_bps: maybe_slippage_tolerance_bps,
+            exchange_id: Some(self.exchange_id),
+        })
+        .await
+    }
+
+    /// Quotes each payload concurrently, returning quotes in input order. Payloads without an
+    /// exchange_id use the client's.
+    pub async fn get_modify_position_quotes(
+        &self,
+        requests: &[ModifyPositionQuotePayload],
+    ) -> Result<Vec<ModifyPositionQuote>> {
+        for request in requests {
+            validate_pubkey("owner", request.owner)?;
+            validate_identifier("margin_account_id", request.margin_account_id.address())?;
+        }
+        buffered_try_flatten(
+            requests
+                .iter()
+                .map(|request| async move {
+                    self.get_modify_position_quote_from_payload(*request)
+                        .await
+                        .map(|quote| vec![quote])
+                })
+                .collect::<Vec<_>>(),
+            self.max_concurrent_requests,
+        )
+        .await
+    }
+
+    async fn get_modify_position_quote_from_payload(
+        &self,
+        mut payload: ModifyPositionQuotePayload,
+    ) -> Result<ModifyPositionQuote> {
+        payload.exchange_id.get_or_insert(self.exchange_id);
+        let response = self
+            .send(
+                self.client
+                    .post(self.build_url(MODIFY_POSITION_QUOTE_PATH))
+                    .json(&payload),
+            )
+            .await?;
+        validate_and_deserialize_response::<ModifyPositionQuote>(response).await
+    }
+
+    /// Quotes closing the account's position in market_id. The api has no close quote route, so
+    /// this fetches the margin account and quotes a modify of the negated position size.
+    pub async fn get_close_position_quote(
+        &self,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        slippage_setting: SlippageSetting,
+    ) -> Result<ModifyPositionQuote> {
+        validate_pubkey("owner", owner)?;
+        let size_delta = self
+            .get_size_delta_to_target(owner, margin_account_id, market_id, 0)
+            .await?;
+        self.get_modify_position_quote(
+            owner,
+            margin_account_id,
+            market_id,
+            size_delta,
+            slippage_setting,
+        )
+        .await
+    }
+}
+
+async fn validate_response(response: Response) -> Result<Response> {
+    if !response.status().is_success() {
+        return Err(ParclV3ApiClientError::Request(
+            response.status(),
+            response.text().await.unwrap_or_default(),
+        ));
+    }
+    Ok(response)
+}
+
+// The all-zeros pubkey (Pubkey::default, the system program id) is never a valid owner or account.
+fn validate_pubkey(name: &str, pubkey: Pubkey) -> Result<()> {
+    if pubkey == Pubkey::default() {
+        return Err(ParclV3ApiClientError::InvalidInput(format!(
+            "{name} must not be the default pubkey"
+        )));
+    }
+    Ok(())
+}
+
+// Ids are passed through as is; address identifiers get the same check as plain pubkeys.
+fn validate_identifier(name: &str, address: Option<Pubkey>) -> Result<()> {
+    address.map_or(Ok(()), |address| validate_pubkey(name, address))
+}
+
+fn not_found_as_none<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(ParclV3ApiClientError::Request(StatusCode::NOT_FOUND, _)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+async fn validate_and_deserialize_response<T: serde::de::DeserializeOwned>(
+    response: Response,
+) -> Result<T> {
+    let body = validate_response(response).await?.bytes().await?;
+    // 204s and other empty bodies deserialize as null so T = () or Option<_> succeed.
+    if body.is_empty() {
+        return serde_json::from_slice::<T>(b"null").map_err(Into::into);
+    }
+    serde_json::from_slice::<T>(&body).map_err(Into::into)
+}
@@ -1 +1,43 @@
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
 pub const DEFAULT_V3_API_URL: &str = "https://v3.parcl-api.com/v1";
+pub const DEFAULT_USER_AGENT: &str = concat!("parcl-v3-api-client-rs/", env!("CARGO_PKG_VERSION"));
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+pub const MAX_BATCH_CHUNK_SIZE: usize = 100;
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+pub const API_VERSION_MEDIA_TYPE: &str = "application/vnd.parcl.v3+json";
+pub const PARCL_V3_PROGRAM_ID: Pubkey = pubkey!("3parcLrT7WnXAcyPfkCz49oofuuf2guUKkjuFkAhZW8Y");
+pub const EXCHANGE_SEED: &[u8] = b"exchange";
+pub const MARKET_SEED: &[u8] = b"market";
+pub const MARGIN_ACCOUNT_SEED: &[u8] = b"margin_account";
+pub const SETTLEMENT_REQUEST_SEED: &[u8] = b"settlement_request";
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_SLIPPAGE_TOLERANCE_BPS: u16 = 10_000;
+pub const FUNDING_PER_UNIT_SCALE: i128 = 1_000_000_000_000_000_000;
+pub const FUNDING_RATE_SCALE: i128 = 1_000_000_000_000_000_000;
+pub const DAYS_PER_YEAR: f64 = 365.0;
+pub const SOL_DECIMALS: u32 = 9;
+pub const EXCHANGE_PATH: &str = "/exchange";
+pub const EXPONENTS_PATH: &str = "/exponents";
+pub const MARKET_IDS_PATH: &str = "/market-ids";
+pub const MARGIN_ACCOUNT_PATH: &str = "/margin-account";
+pub const MARGIN_ACCOUNTS_PATH: &str = "/margin-accounts";
+pub const UNHEALTHY_MARGIN_ACCOUNTS_PATH: &str = "/unhealthy-margin-accounts";
+pub const MARKET_PATH: &str = "/market";
+pub const MARKETS_PATH: &str = "/markets";
+pub const CREATE_MARGIN_ACCOUNT_TRANSACTION_PATH: &str = "/create-margin-account-transaction";
+pub const CREATE_MARGIN_ACCOUNT_INSTRUCTIONS_PATH: &str = "/create-margin-account-instructions";
+pub const CLOSE_MARGIN_ACCOUNT_TRANSACTION_PATH: &str = "/close-margin-account-transaction";
+pub const CLOSE_MARGIN_ACCOUNT_INSTRUCTIONS_PATH: &str = "/close-margin-account-instructions";
+pub const DEPOSIT_MARGIN_TRANSACTION_PATH: &str = "/deposit-margin-transaction";
+pub const DEPOSIT_MARGIN_INSTRUCTIONS_PATH: &str = "/deposit-margin-instructions";
+pub const WITHDRAW_MARGIN_TRANSACTION_PATH: &str = "/withdraw-margin-transaction";
+pub const WITHDRAW_MARGIN_INSTRUCTIONS_PATH: &str = "/withdraw-margin-instructions";
+pub const MODIFY_POSITION_TRANSACTION_PATH: &str = "/modify-position-transaction";
+pub const MODIFY_POSITION_INSTRUCTIONS_PATH: &str = "/modify-position-instructions";
+pub const CLOSE_POSITION_TRANSACTION_PATH: &str = "/close-position-transaction";
+pub const CLOSE_POSITION_INSTRUCTIONS_PATH: &str = "/close-position-instructions";
+pub const LIQUIDATE_TRANSACTION_PATH: &str = "/liquidate-transaction";
+pub const LIQUIDATE_INSTRUCTIONS_PATH: &str = "/liquidate-instructions";
+pub const MODIFY_POSITION_QUOTE_PATH: &str = "/modify-position-quote";
@@ -1,12 +1,20 @@
-use crate::serde_utils::{field_as_string, optional_field_as_string, pubkey_vec};
+use crate::{
+    constants::MAX_SLIPPAGE_TOLERANCE_BPS,
+    serde_utils::{field_as_string, optional_field_as_string, pubkey_vec},
+    ParclV3ApiClientError, Result,
+};
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
 
 pub type ExchangeId = u64;
 pub type MarginAccountId = u32;
 pub type MarketId = u32;
+/// The api has no routes to list or cancel settlement requests. Keep the id passed to withdraw
+/// margin if you need to refer to the request later.
 pub type SettlementRequestId = u64;
 
+/// The api serves a single exchange (id 0, the default) and has no endpoint to list exchanges.
 #[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 #[serde(untagged)]
 pub enum ExchangeIdentifier {
@@ -35,6 +43,19 @@ impl std::fmt::Display for ExchangeIdentifier {
     }
 }
 
+impl FromStr for ExchangeIdentifier {
+    type Err = ParclV3ApiClientError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Ok(id) = s.parse::<ExchangeId>() {
+            return Ok(Self::Id(id));
+        }
+        Pubkey::from_str(s)
+            .map(Self::Address)
+            .map_err(|_| ParclV3ApiClientError::InvalidIdentifier(s.to_string()))
+    }
+}
+
 #[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 #[serde(untagged)]
 pub enum MarginAccountIdentifier {
@@ -57,6 +78,29 @@ impl std::fmt::Display for MarginAccountIdentifier {
     }
 }
 
+impl MarginAccountIdentifier {
+    /// Returns the address for Address identifiers and None for ids.
+    pub fn address(&self) -> Option<Pubkey> {
+        match self {
+            Self::Id(_) => None,
+            Self::Address(address) => Some(*address),
+        }
+    }
+}
+
+impl FromStr for MarginAccountIdentifier {
+    type Err = ParclV3ApiClientError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Ok(id) = s.parse::<MarginAccountId>() {
+            return Ok(Self::Id(id));
+        }
+        Pubkey::from_str(s)
+            .map(Self::Address)
+            .map_err(|_| ParclV3ApiClientError::InvalidIdentifier(s.to_string()))
+    }
+}
+
 #[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 #[serde(untagged)]
 pub enum MarketIdentifier {
@@ -79,6 +123,29 @@ impl std::fmt::Display for MarketIdentifier {
     }
 }
 
+impl MarketIdentifier {
+    /// Returns the address for Address identifiers and None for ids.
+    pub fn address(&self) -> Option<Pubkey> {
+        match self {
+            Self::Id(_) => None,
+            Self::Address(address) => Some(*address),
+        }
+    }
+}
+
+impl FromStr for MarketIdentifier {
+    type Err = ParclV3ApiClientError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Ok(id) = s.parse::<MarketId>() {
+            return Ok(Self::Id(id));
+        }
+        Pubkey::from_str(s)
+            .map(Self::Address)
+            .map_err(|_| ParclV3ApiClientError::InvalidIdentifier(s.to_string()))
+    }
+}
+
 #[derive(Deserialize, Serialize, Default, Debug, Copy, Clone)]
 pub enum MarketIdentifiersResponseKind {
     #[default]
@@ -104,17 +171,87 @@ impl std::fmt::Display for MarketIdentifiersResponseKind {
     }
 }
 
+impl FromStr for MarketIdentifiersResponseKind {
+    type Err = ParclV3ApiClientError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ids" => Ok(Self::Ids),
+            "map" => Ok(Self::Map),
+            "addresses" => Ok(Self::Addresses),
+            _ => Err(ParclV3ApiClientError::InvalidMarketIdentifiersResponseKind(
+                s.to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone)]
+pub struct PageParams {
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum SlippageSetting {
     AcceptablePrice(u64),
     SlippageToleranceBps(u16),
+    /// Sends both fields. How the server combines them is unverified: presumably it applies the
+    /// tolerance to the index price and rejects fills past either bound, but no api contract says so.
+    Both {
+        acceptable_price: u64,
+        slippage_tolerance_bps: u16,
+    },
 }
 
 impl SlippageSetting {
+    pub fn tolerance_bps(bps: u16) -> Result<Self> {
+        if bps > MAX_SLIPPAGE_TOLERANCE_BPS {
+            return Err(ParclV3ApiClientError::InvalidSlippageToleranceBps(bps));
+        }
+        Ok(Self::SlippageToleranceBps(bps))
+    }
+
+    pub fn acceptable_price(price: u64) -> Result<Self> {
+        if price == 0 {
+            return Err(ParclV3ApiClientError::InvalidAcceptablePrice);
+        }
+        Ok(Self::AcceptablePrice(price))
+    }
+
+    pub fn both(acceptable_price: u64, slippage_tolerance_bps: u16) -> Result<Self> {
+        Self::acceptable_price(acceptable_price)?;
+        Self::tolerance_bps(slippage_tolerance_bps)?;
+        Ok(Self::Both {
+            acceptable_price,
+            slippage_tolerance_bps,
+        })
+    }
+
     pub fn as_request_fields(&self) -> (Option<u64>, Option<u16>) {
         match self {
             SlippageSetting::AcceptablePrice(price) => (Some(*price), None),
             SlippageSetting::SlippageToleranceBps(bps) => (None, Some(*bps)),
+            SlippageSetting::Both {
+                acceptable_price,
+                slippage_tolerance_bps,
+            } => (Some(*acceptable_price), Some(*slippage_tolerance_bps)),
+        }
+    }
+}
+
+impl std::fmt::Display for SlippageSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AcceptablePrice(price) => write!(f, "acceptable_price={price}"),
+            Self::SlippageToleranceBps(bps) => write!(f, "slippage={bps}bps"),
+            Self::Both {
+                acceptable_price,
+                slippage_tolerance_bps,
+            } => write!(
+                f,
+                "acceptable_price={acceptable_price} slippage={slippage_tolerance_bps}bps"
+            ),
         }
     }
 }
@@ -173,6 +310,8 @@ pub struct DepositMarginPayload {
     pub priority_fee_percentile: Option<u16>,
 }
 
+/// Position transactions are always built for owner to sign. The api does not support building
+/// them for a margin account's delegate.
 #[derive(Deserialize, Serialize, Debug)]
 pub struct ModifyPositionPayload {
     #[serde(with = "field_as_string")]
@@ -208,6 +347,29 @@ impl ModifyPositionPayload {
             priority_fee_percentile: None,
         }
     }
+
+    #[cfg(feature = "client")]
+    pub fn new_for_client(
+        client: &crate::ParclV3ApiClient,
+        owner: Pubkey,
+        margin_account_id: MarginAccountIdentifier,
+        market_id: MarketId,
+        size_delta: i128,
+        slippage_setting: SlippageSetting,
+        priority_fee_percentile: Option<u16>,
+    ) -> Self {
+        Self {
+            exchange_id: Some(client.exchange_id()),
+            priority_fee_percentile: priority_fee_percentile.or(client.priority_fee_percentile()),
+            ..Self::new_with_defaults(
+                owner,
+                margin_account_id,
+                market_id,
+                size_delta,
+                slippage_setting,
+            )
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -233,7 +395,7 @@ pub struct LiquidatePayload {
     pub priority_fee_percentile: Option<u16>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 pub struct ModifyPositionQuotePayload {
     #[serde(with = "field_as_string")]
     pub owner: Pubkey,
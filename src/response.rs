@@ -1,15 +1,35 @@
 use crate::{
+    constants::*,
     request::*,
-    serde_utils::{field_as_base64, field_as_string, pubkey_values_map, pubkey_vec},
+    serde_utils::{
+        field_as_base64, field_as_string, number_or_string, pubkey_values_map, pubkey_vec,
+    },
+    utils::{decode_transaction_instructions, notional_value},
+    ParclV3ApiClientError, Result,
 };
-use serde::Deserialize;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use solana_sdk::{
+    compute_budget::{self, ComputeBudgetInstruction},
+    hash::Hash,
     instruction::{AccountMeta, Instruction},
+    message::{v0, VersionedMessage},
+    native_token::lamports_to_sol,
     pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Instant,
 };
-use std::collections::HashMap;
 
-#[derive(Deserialize, Debug)]
+/// Unsigned transaction built by the api. The api returns no simulation data, so simulate the
+/// transaction against an rpc node if you need logs or compute units consumed.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct TransactionInfo {
     #[serde(with = "field_as_base64")]
     pub transaction: Vec<u8>,
@@ -19,7 +39,91 @@ pub struct TransactionInfo {
     pub cu_limit: u32,
 }
 
-#[derive(Deserialize, Debug)]
+impl TransactionInfo {
+    pub fn lamport_requirements(&self) -> LamportRequirements {
+        LamportRequirements {
+            total: self.total_required_lamports,
+            compute: self.required_compute_lamports,
+            rent: self.required_rent_lamports,
+        }
+    }
+
+    pub fn versioned_transaction(&self) -> Result<VersionedTransaction> {
+        bincode::deserialize(&self.transaction).map_err(Into::into)
+    }
+
+    /// Any existing signatures are cleared since they no longer cover the message.
+    pub fn with_recent_blockhash(&self, blockhash: Hash) -> Result<VersionedTransaction> {
+        let mut tx = self.versioned_transaction()?;
+        tx.message.set_recent_blockhash(blockhash);
+        tx.signatures.fill(Signature::default());
+        Ok(tx)
+    }
+
+    /// Decodes the transaction's instructions locally, splitting compute budget instructions from
+    /// the rest. Errors if an instruction uses an address lookup table account.
+    pub fn decode_instructions(&self) -> Result<Instructions> {
+        let (compute_budget_instructions, v3_instructions) =
+            decode_transaction_instructions(&self.transaction)?
+                .into_iter()
+                .partition(|ix| ix.program_id == compute_budget::id());
+        Ok(Instructions {
+            v3_instructions,
+            compute_budget_instructions,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LamportRequirements {
+    #[serde(rename = "total_required_lamports")]
+    pub total: u64,
+    #[serde(rename = "required_compute_lamports")]
+    pub compute: u64,
+    #[serde(rename = "required_rent_lamports")]
+    pub rent: u64,
+}
+
+impl LamportRequirements {
+    /// Compute lamports are what the compute budget price charges, i.e. the priority fee.
+    pub fn priority_fee(&self) -> u64 {
+        self.compute
+    }
+
+    pub fn total_sol(&self) -> f64 {
+        lamports_to_sol(self.total)
+    }
+
+    pub fn compute_sol(&self) -> f64 {
+        lamports_to_sol(self.compute)
+    }
+
+    pub fn rent_sol(&self) -> f64 {
+        lamports_to_sol(self.rent)
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    pub fn total_sol_decimal(&self) -> Decimal {
+        lamports_to_sol_decimal(self.total)
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    pub fn compute_sol_decimal(&self) -> Decimal {
+        lamports_to_sol_decimal(self.compute)
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    pub fn rent_sol_decimal(&self) -> Decimal {
+        lamports_to_sol_decimal(self.rent)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+fn lamports_to_sol_decimal(lamports: u64) -> Decimal {
+    Decimal::from_i128_with_scale(lamports as i128, SOL_DECIMALS)
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct InstructionInfo {
     pub instructions: Instructions,
     pub total_required_lamports: u64,
@@ -28,6 +132,37 @@ pub struct InstructionInfo {
     pub cu_limit: u32,
 }
 
+impl InstructionInfo {
+    pub fn lamport_requirements(&self) -> LamportRequirements {
+        LamportRequirements {
+            total: self.total_required_lamports,
+            compute: self.required_compute_lamports,
+            rent: self.required_rent_lamports,
+        }
+    }
+
+    pub fn build_transaction(
+        &self,
+        payer: &Pubkey,
+        blockhash: Hash,
+        extra_ixs: &[Instruction],
+    ) -> Result<VersionedTransaction> {
+        let ixs = self
+            .instructions
+            .compute_budget_instructions
+            .iter()
+            .chain(self.instructions.v3_instructions.iter())
+            .chain(extra_ixs.iter())
+            .cloned()
+            .collect::<Vec<Instruction>>();
+        let message = v0::Message::try_compile(payer, &ixs, &[], blockhash)?;
+        Ok(VersionedTransaction {
+            signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+            message: VersionedMessage::V0(message),
+        })
+    }
+}
+
 impl From<InstructionInfoInternal> for InstructionInfo {
     fn from(ixs: InstructionInfoInternal) -> Self {
         InstructionInfo {
@@ -40,7 +175,7 @@ impl From<InstructionInfoInternal> for InstructionInfo {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct InstructionInfoInternal {
     pub instructions: InstructionsInternal,
     pub total_required_lamports: u64,
@@ -49,18 +184,109 @@ pub struct InstructionInfoInternal {
     pub cu_limit: u32,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct Instructions {
     pub v3_instructions: Vec<Instruction>,
     pub compute_budget_instructions: Vec<Instruction>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct InstructionsInternal {
     pub v3_instructions: Vec<InstructionInternal>,
     pub compute_budget_instructions: Vec<InstructionInternal>,
 }
 
+impl Instructions {
+    /// Concatenates v3 instructions in order. Compute budget instructions are deduplicated by kind.
+    /// Compute unit limits are summed, since the merged instructions run in one transaction, and
+    /// capped at MAX_COMPUTE_UNIT_LIMIT. Other kinds keep the largest value (e.g. the max price).
+    pub fn merge(sets: &[&Instructions]) -> Instructions {
+        let mut compute_budget_instructions: BTreeMap<u8, (u64, Instruction)> = BTreeMap::new();
+        let mut other_compute_budget_instructions: Vec<Instruction> = Vec::new();
+        let mut compute_unit_limit = 0u64;
+        for ix in sets
+            .iter()
+            .flat_map(|set| set.compute_budget_instructions.iter())
+        {
+            match decode_compute_budget_instruction(ix) {
+                Some((SET_COMPUTE_UNIT_LIMIT_KIND, value)) => {
+                    compute_unit_limit = compute_unit_limit.saturating_add(value);
+                    compute_budget_instructions
+                        .entry(SET_COMPUTE_UNIT_LIMIT_KIND)
+                        .or_insert_with(|| (value, ix.clone()));
+                }
+                Some((kind, value)) => {
+                    let entry = compute_budget_instructions
+                        .entry(kind)
+                        .or_insert_with(|| (value, ix.clone()));
+                    if value > entry.0 {
+                        *entry = (value, ix.clone());
+                    }
+                }
+                None => {
+                    if !other_compute_budget_instructions.contains(ix) {
+                        other_compute_budget_instructions.push(ix.clone());
+                    }
+                }
+            }
+        }
+        if let Some(entry) = compute_budget_instructions.get_mut(&SET_COMPUTE_UNIT_LIMIT_KIND) {
+            entry.1 = ComputeBudgetInstruction::set_compute_unit_limit(
+                compute_unit_limit.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32,
+            );
+        }
+        Instructions {
+            v3_instructions: sets
+                .iter()
+                .flat_map(|set| set.v3_instructions.iter().cloned())
+                .collect(),
+            compute_budget_instructions: compute_budget_instructions
+                .into_values()
+                .map(|(_, ix)| ix)
+                .chain(other_compute_budget_instructions)
+                .collect(),
+        }
+    }
+
+    pub fn verify_program_ids(&self, expected: &Pubkey) -> Result<()> {
+        for ix in self.v3_instructions.iter() {
+            if ix.program_id != *expected {
+                return Err(ParclV3ApiClientError::UnexpectedProgramId(
+                    *expected,
+                    ix.program_id,
+                ));
+            }
+        }
+        for ix in self.compute_budget_instructions.iter() {
+            if ix.program_id != compute_budget::id() {
+                return Err(ParclV3ApiClientError::UnexpectedProgramId(
+                    compute_budget::id(),
+                    ix.program_id,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ComputeBudgetInstruction::SetComputeUnitLimit's borsh discriminant.
+const SET_COMPUTE_UNIT_LIMIT_KIND: u8 = 2;
+
+// Returns the instruction discriminant and its u32/u64 little endian argument. Instructions for
+// other programs return None.
+fn decode_compute_budget_instruction(ix: &Instruction) -> Option<(u8, u64)> {
+    if ix.program_id != compute_budget::id() {
+        return None;
+    }
+    let (kind, data) = ix.data.split_first()?;
+    let value = match data.len() {
+        4 => u32::from_le_bytes(data.try_into().ok()?) as u64,
+        8 => u64::from_le_bytes(data.try_into().ok()?),
+        _ => return None,
+    };
+    Some((*kind, value))
+}
+
 impl From<InstructionsInternal> for Instructions {
     fn from(ixs: InstructionsInternal) -> Self {
         Instructions {
@@ -78,7 +304,7 @@ impl From<InstructionsInternal> for Instructions {
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct InstructionInternal {
     #[serde(with = "field_as_string")]
     pub program_id: Pubkey,
@@ -97,7 +323,7 @@ impl From<InstructionInternal> for Instruction {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub struct AccountMetaInternal {
     #[serde(with = "field_as_string")]
     pub pubkey: Pubkey,
@@ -115,7 +341,7 @@ impl From<AccountMetaInternal> for AccountMeta {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct CreateMarginAccountTransactionResponse {
     #[serde(with = "field_as_base64")]
     pub transaction: Vec<u8>,
@@ -127,7 +353,17 @@ pub struct CreateMarginAccountTransactionResponse {
     pub margin_account_id: MarginAccountId,
 }
 
-#[derive(Deserialize, Debug)]
+impl CreateMarginAccountTransactionResponse {
+    pub fn lamport_requirements(&self) -> LamportRequirements {
+        LamportRequirements {
+            total: self.total_required_lamports,
+            compute: self.required_compute_lamports,
+            rent: self.required_rent_lamports,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct CreateMarginAccountInstructionsResponse {
     pub instructions: Instructions,
     pub total_required_lamports: u64,
@@ -138,6 +374,16 @@ pub struct CreateMarginAccountInstructionsResponse {
     pub margin_account_id: MarginAccountId,
 }
 
+impl CreateMarginAccountInstructionsResponse {
+    pub fn lamport_requirements(&self) -> LamportRequirements {
+        LamportRequirements {
+            total: self.total_required_lamports,
+            compute: self.required_compute_lamports,
+            rent: self.required_rent_lamports,
+        }
+    }
+}
+
 impl From<CreateMarginAccountInstructionsResponseInternal>
     for CreateMarginAccountInstructionsResponse
 {
@@ -153,7 +399,7 @@ impl From<CreateMarginAccountInstructionsResponseInternal>
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct CreateMarginAccountInstructionsResponseInternal {
     pub instructions: InstructionsInternal,
     pub total_required_lamports: u64,
@@ -164,7 +410,7 @@ pub struct CreateMarginAccountInstructionsResponseInternal {
     pub margin_account_id: MarginAccountId,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct ExchangeInfo {
     #[serde(with = "field_as_string")]
     pub address: Pubkey,
@@ -174,6 +420,7 @@ pub struct ExchangeInfo {
     pub id: ExchangeId,
     pub market_ids: Vec<MarketId>,
     pub oracle_configs: Vec<OracleConfig>,
+    #[serde(with = "number_or_string")]
     pub status: u16,
     pub collateral_expo: i16,
     #[serde(with = "field_as_string")]
@@ -190,7 +437,50 @@ pub struct ExchangeInfo {
     pub authorized_protocol_fees_collector: Pubkey,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl ExchangeInfo {
+    /// The api does not say which oracle backs a market, so pass the owner program of
+    /// market.price_feed (fetched over rpc). Returns None if the market is on another exchange.
+    pub fn oracle_for_market(
+        &self,
+        market: &MarketInfo,
+        price_feed_owner: &Pubkey,
+    ) -> Option<&OracleConfig> {
+        if market.exchange != self.address {
+            return None;
+        }
+        self.oracle_configs
+            .iter()
+            .find(|oracle_config| oracle_config.program_id == *price_feed_owner)
+    }
+
+    pub fn collateral_to_ui(&self, raw: u64) -> f64 {
+        raw as f64 * 10f64.powi(self.collateral_expo as i32)
+    }
+
+    /// Rounds to the nearest raw unit. Negative and out of range amounts saturate.
+    pub fn ui_to_collateral(&self, ui: f64) -> u64 {
+        (ui * 10f64.powi(-(self.collateral_expo as i32))).round() as u64
+    }
+
+    pub fn protocol_fees_ui(&self) -> f64 {
+        self.collateral_to_ui(self.accounting.protocol_fees)
+    }
+
+    pub fn balance_ui(&self) -> f64 {
+        self.collateral_to_ui(self.accounting.balance)
+    }
+
+    pub fn margin_balance_ui(&self) -> f64 {
+        self.collateral_to_ui(self.accounting.margin_balance)
+    }
+
+    pub fn lp_balance_ui(&self) -> f64 {
+        self.collateral_to_ui(self.accounting.lp_balance)
+    }
+}
+
+/// The api has no lp deposit or withdraw routes, so lp_balance and lp_shares are read only here.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct ExchangeInfoAccounting {
     #[serde(with = "field_as_string")]
     pub notional_open_interest: u128,
@@ -210,28 +500,48 @@ pub struct ExchangeInfoAccounting {
     pub unsettled_collateral_amount: u64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[cfg(feature = "chrono")]
+impl ExchangeInfoAccounting {
+    pub fn last_locked_open_interest_accounting_refreshed_at(&self) -> DateTime<Utc> {
+        unix_seconds_to_date_time(self.last_time_locked_open_interest_accounting_refreshed)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct ExchangeInfoSettings {
+    #[serde(with = "number_or_string")]
     pub min_lp_duration: u64,
+    #[serde(with = "number_or_string")]
     pub settlement_delay: u64,
     #[serde(with = "field_as_string")]
     pub min_liquidation_fee: u64,
     #[serde(with = "field_as_string")]
     pub max_liquidation_fee: u64,
+    #[serde(with = "number_or_string")]
     pub locked_open_interest_staleness_threshold: u64,
+    #[serde(with = "number_or_string")]
     pub protocol_fee_rate: u16,
+    #[serde(with = "number_or_string")]
     pub locked_open_interest_ratio: u16,
+    #[serde(with = "number_or_string")]
     pub max_keeper_tip_rate: u16,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl ExchangeInfoSettings {
+    pub fn max_keeper_tip(&self, amount: u64) -> u64 {
+        // Rounds down so the tip never exceeds max_keeper_tip_rate bps of amount.
+        (amount as u128 * self.max_keeper_tip_rate as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct OracleConfig {
     pub kind: OracleKind,
     #[serde(with = "field_as_string")]
     pub program_id: Pubkey,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum OracleKind {
     #[serde(rename = "pyth")]
     Pyth,
@@ -241,7 +551,7 @@ pub enum OracleKind {
     PythV2,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum MarketIdentifiersResponse {
     Ids(Vec<MarketId>),
@@ -250,7 +560,49 @@ pub enum MarketIdentifiersResponse {
     Map(HashMap<MarketId, Pubkey>),
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl MarketIdentifiersResponse {
+    /// Untagged deserialization can't tell an empty Ids list from an empty Addresses list, so
+    /// deserialize using the kind that was requested instead.
+    pub fn from_json_value(
+        response_kind: MarketIdentifiersResponseKind,
+        value: serde_json::Value,
+    ) -> Result<Self> {
+        Ok(match response_kind {
+            MarketIdentifiersResponseKind::Ids => Self::Ids(serde_json::from_value(value)?),
+            MarketIdentifiersResponseKind::Addresses => {
+                Self::Addresses(pubkey_vec::deserialize(value)?)
+            }
+            MarketIdentifiersResponseKind::Map => Self::Map(pubkey_values_map::deserialize(value)?),
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct MarketIdsPage {
+    pub market_ids: Vec<MarketId>,
+    pub next_offset: Option<u32>,
+}
+
+impl MarketIdsPage {
+    pub fn from_market_ids(market_ids: Vec<MarketId>, page: PageParams) -> Self {
+        let offset = page.offset.unwrap_or(0) as usize;
+        let end = match page.limit {
+            Some(limit) => offset.saturating_add(limit as usize),
+            None => usize::MAX,
+        };
+        let next_offset = (end < market_ids.len()).then_some(end as u32);
+        Self {
+            market_ids: market_ids
+                .into_iter()
+                .skip(offset)
+                .take(end - offset)
+                .collect(),
+            next_offset,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct MarginAccountInfo {
     #[serde(with = "field_as_string")]
     pub address: Pubkey,
@@ -273,7 +625,239 @@ pub struct MarginAccountInfo {
     pub in_liquidation: bool,
 }
 
-#[derive(Deserialize, Debug, Default, PartialEq, Clone)]
+impl MarginAccountInfo {
+    /// Accounts the api flags as liquidatable are BelowMaintenanceMargin when available_margin is
+    /// under required_maintenance_margin and Liquidatable otherwise.
+    pub fn liquidation_status(&self) -> LiquidationStatus {
+        if self.in_liquidation {
+            LiquidationStatus::InLiquidation
+        } else if self.can_liquidate
+            && self.margins.available_margin < self.margins.required_maintenance_margin as i128
+        {
+            LiquidationStatus::BelowMaintenanceMargin {
+                available_margin: self.margins.available_margin,
+                required_maintenance_margin: self.margins.required_maintenance_margin,
+            }
+        } else if self.can_liquidate {
+            LiquidationStatus::Liquidatable
+        } else if self.positions.iter().all(|position| position.size == 0) {
+            LiquidationStatus::NoPositions
+        } else {
+            LiquidationStatus::Healthy
+        }
+    }
+
+    pub fn summary(&self) -> MarginAccountSummary {
+        MarginAccountSummary {
+            address: self.address,
+            id: self.id,
+            owner: self.owner,
+            margin: self.margin,
+            excess_margin: self.excess_margin,
+            available_margin: self.margins.available_margin,
+            total_required_margin: self.margins.total_required_margin,
+            can_close: self.can_close,
+            can_liquidate: self.can_liquidate,
+            in_liquidation: self.in_liquidation,
+            position_count: self.positions.len(),
+        }
+    }
+
+    pub fn positions_by_market(&self) -> HashMap<MarketId, &PositionInfo> {
+        self.positions
+            .iter()
+            .map(|position| (position.market_id, position))
+            .collect()
+    }
+
+    pub fn position_for_market(&self, market_id: MarketId) -> Option<&PositionInfo> {
+        self.positions
+            .iter()
+            .find(|position| position.market_id == market_id)
+    }
+
+    pub fn position_size(&self, market_id: MarketId) -> i128 {
+        self.position_for_market(market_id)
+            .map(|position| position.size)
+            .unwrap_or_default()
+    }
+
+    /// Sums size * (current_price - last_interaction_price) across positions. The result is not
+    /// rescaled, so it carries the precision of size multiplied by the precision of price.
+    /// Positions without an entry in prices are skipped. Returns None if the sum overflows i128.
+    pub fn unrealized_pnl(&self, prices: &HashMap<MarketId, u128>) -> Option<i128> {
+        self.positions
+            .iter()
+            .filter_map(|position| {
+                prices
+                    .get(&position.market_id)
+                    .map(|price| position_unrealized_pnl(position, *price))
+            })
+            .try_fold(0i128, |total, pnl| total.checked_add(pnl?))
+    }
+
+    /// Sums |size| * price across positions, with the same precision and missing price handling
+    /// as unrealized_pnl.
+    pub fn total_notional(&self, prices: &HashMap<MarketId, u128>) -> u128 {
+        self.positions
+            .iter()
+            .filter_map(|position| {
+                prices
+                    .get(&position.market_id)
+                    .map(|price| position.size.unsigned_abs().saturating_mul(*price))
+            })
+            .fold(0u128, u128::saturating_add)
+    }
+
+    /// total_notional divided by margin. Prices must be scaled so that size * price is in raw
+    /// collateral units for this to be meaningful. None if the account has no margin.
+    pub fn leverage(&self, prices: &HashMap<MarketId, u128>) -> Option<f64> {
+        if self.margin == 0 {
+            return None;
+        }
+        Some(self.total_notional(prices) as f64 / self.margin as f64)
+    }
+
+    /// Same as unrealized_pnl but errors if any position is missing a price.
+    pub fn try_unrealized_pnl(&self, prices: &HashMap<MarketId, u128>) -> Result<Option<i128>> {
+        let mut total = Some(0i128);
+        for position in self.positions.iter() {
+            let price = prices.get(&position.market_id).ok_or(
+                ParclV3ApiClientError::MissingMarketPrice(position.market_id),
+            )?;
+            total = total
+                .zip(position_unrealized_pnl(position, *price))
+                .and_then(|(total, pnl)| total.checked_add(pnl));
+        }
+        Ok(total)
+    }
+
+    /// Solves for the price (in the market's price feed exponent) at which this account's margin
+    /// plus the position's pnl falls to its maintenance margin plus the exchange's
+    /// min_liquidation_fee. Assumes the position is the account's only exposure, uses the market's
+    /// initial_margin_ratio and ignores funding, fees, and min_position_margin. Returns None if
+    /// there is no position or it cannot be liquidated at any positive price.
+    pub fn liquidation_price(&self, market: &MarketInfo, exchange: &ExchangeInfo) -> Option<u64> {
+        let position = self.position_for_market(market.id)?;
+        if position.size == 0 {
+            return None;
+        }
+        let entry_price = i128::try_from(position.last_interaction_price).ok()?;
+        // notional = |size| * price * scale_num / scale_den
+        let expo = market.price_feed_info.expo;
+        let (scale_num, scale_den) = if expo < 0 {
+            (1i128, 10i128.checked_pow(expo.unsigned_abs())?)
+        } else {
+            (10i128.checked_pow(expo as u32)?, 1i128)
+        };
+        // maintenance margin = notional * mm_ratio / mm_denominator
+        let mm_denominator = (BPS_DENOMINATOR as i128).pow(2);
+        let mm_ratio = market.settings.initial_margin_ratio as i128
+            * market.settings.maintenance_margin_proportion as i128;
+        let buffer = (self.margin as i128 - exchange.settings.min_liquidation_fee as i128)
+            .checked_mul(scale_den)?;
+        let scaled_size = position.size.checked_mul(scale_num)?;
+        // Solves margin + size * (price - entry) = |size| * price * mm_ratio for price.
+        let numerator = scaled_size.checked_mul(entry_price)?.checked_sub(buffer)?;
+        let denominator = scaled_size.checked_mul(if position.size > 0 {
+            mm_denominator - mm_ratio
+        } else {
+            mm_denominator + mm_ratio
+        })?;
+        if numerator == 0 || denominator == 0 || (numerator > 0) != (denominator > 0) {
+            return None;
+        }
+        u64::try_from(numerator.checked_mul(mm_denominator)? / denominator).ok()
+    }
+
+    /// Compares self (the earlier snapshot) against other (the later snapshot) and returns the
+    /// positions that changed, ordered by market id.
+    pub fn position_diff(&self, other: &MarginAccountInfo) -> Vec<PositionChange> {
+        let mut sizes: BTreeMap<MarketId, (i128, i128)> = BTreeMap::new();
+        for position in self.positions.iter() {
+            sizes.entry(position.market_id).or_default().0 = position.size;
+        }
+        for position in other.positions.iter() {
+            sizes.entry(position.market_id).or_default().1 = position.size;
+        }
+        sizes
+            .into_iter()
+            .filter(|(_, (previous_size, new_size))| previous_size != new_size)
+            .map(|(market_id, (previous_size, new_size))| PositionChange {
+                market_id,
+                previous_size,
+                new_size,
+                size_delta: new_size - previous_size,
+                opened: previous_size == 0,
+                closed: new_size == 0,
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LiquidationStatus {
+    Healthy,
+    NoPositions,
+    InLiquidation,
+    BelowMaintenanceMargin {
+        available_margin: i128,
+        required_maintenance_margin: u64,
+    },
+    /// Flagged liquidatable by the api without available_margin being under
+    /// required_maintenance_margin.
+    Liquidatable,
+}
+
+impl LiquidationStatus {
+    pub fn is_liquidatable(&self) -> bool {
+        matches!(
+            self,
+            Self::InLiquidation | Self::BelowMaintenanceMargin { .. } | Self::Liquidatable
+        )
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct MarginAccountSummary {
+    #[serde(with = "field_as_string")]
+    pub address: Pubkey,
+    pub id: MarginAccountId,
+    #[serde(with = "field_as_string")]
+    pub owner: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub margin: u64,
+    #[serde(with = "field_as_string")]
+    pub excess_margin: u64,
+    #[serde(with = "field_as_string")]
+    pub available_margin: i128,
+    #[serde(with = "field_as_string")]
+    pub total_required_margin: u64,
+    pub can_close: bool,
+    pub can_liquidate: bool,
+    pub in_liquidation: bool,
+    pub position_count: usize,
+}
+
+fn position_unrealized_pnl(position: &PositionInfo, price: u128) -> Option<i128> {
+    let price = i128::try_from(price).ok()?;
+    let last_interaction_price = i128::try_from(position.last_interaction_price).ok()?;
+    position
+        .size
+        .checked_mul(price.checked_sub(last_interaction_price)?)
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PositionChange {
+    pub market_id: MarketId,
+    pub previous_size: i128,
+    pub new_size: i128,
+    pub size_delta: i128,
+    pub opened: bool,
+    pub closed: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, PartialEq, Clone, Eq)]
 pub struct Margins {
     #[serde(with = "field_as_string")]
     pub available_margin: i128,
@@ -289,17 +873,21 @@ pub struct Margins {
     pub accumulated_liquidation_fees: u64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct PositionInfo {
     #[serde(with = "field_as_string")]
     pub size: i128,
     #[serde(with = "field_as_string")]
     pub last_interaction_price: u128,
-    pub last_interaction_funding_per_unit: String,
+    /// Fixed point with FUNDING_PER_UNIT_SCALE (1e18) precision.
+    #[serde(with = "field_as_string")]
+    pub last_interaction_funding_per_unit: i128,
     pub market_id: MarketId,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+/// The api does not return market symbols or names, so markets can only be looked up by id or
+/// address. Keep your own symbol to MarketId table (e.g. "NYC" -> id) if you need one.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct MarketInfo {
     #[serde(with = "field_as_string")]
     pub address: Pubkey,
@@ -311,17 +899,144 @@ pub struct MarketInfo {
     pub exchange: Pubkey,
     #[serde(with = "field_as_string")]
     pub price_feed: Pubkey,
+    #[serde(with = "number_or_string")]
     pub status: u8,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl MarketInfo {
+    /// Initial margin for a position of size at price, floored at min_position_margin. Price uses the
+    /// market's price feed exponent. Returns None if the notional or margin overflows u128.
+    pub fn required_initial_margin(&self, size: i128, price: u64) -> Option<u128> {
+        if size == 0 {
+            return Some(0);
+        }
+        let notional = notional_value(
+            size,
+            &PriceFeedInfo {
+                price,
+                expo: self.price_feed_info.expo,
+            },
+        )?;
+        let margin = notional.checked_mul(self.settings.initial_margin_ratio as u128)?
+            / BPS_DENOMINATOR as u128;
+        Some(margin.max(self.settings.min_position_margin))
+    }
+
+    pub fn market_status(&self) -> MarketStatus {
+        MarketStatus::from(self.status)
+    }
+
+    /// Renders the market as aligned name/value lines. Size and skew are scaled by size_expo, the
+    /// exponent of position sizes (see get_exponents).
+    pub fn display_table(&self, size_expo: i32) -> String {
+        let rows = [
+            ("market", format!("{} ({})", self.id, self.address)),
+            ("status", format!("{:?}", self.market_status())),
+            (
+                "price",
+                scale_to_ui(self.price_feed_info.price as f64, self.price_feed_info.expo)
+                    .to_string(),
+            ),
+            (
+                "size",
+                scale_to_ui(self.accounting.size as f64, size_expo).to_string(),
+            ),
+            (
+                "skew",
+                scale_to_ui(self.accounting.skew as f64, size_expo).to_string(),
+            ),
+            (
+                "funding rate apr",
+                self.accounting
+                    .funding_rate_apr()
+                    .map_or_else(|| "n/a".to_string(), |apr| format!("{apr:.4}%")),
+            ),
+            (
+                "maker fee",
+                format!("{:.2}%", bps_to_percent(self.settings.maker_fee_rate)),
+            ),
+            (
+                "taker fee",
+                format!("{:.2}%", bps_to_percent(self.settings.taker_fee_rate)),
+            ),
+        ];
+        let width = rows
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or_default();
+        rows.iter()
+            .map(|(name, value)| format!("{name:<width$}  {value}"))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarketsSnapshot {
+    pub markets: Vec<MarketInfo>,
+    pub fetched_at: Instant,
+}
+
+impl MarketsSnapshot {
+    pub fn total_open_interest(&self) -> u128 {
+        self.markets
+            .iter()
+            .map(|market| market.accounting.size)
+            .sum()
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.markets
+            .iter()
+            .filter(|market| market.market_status() == MarketStatus::Active)
+            .count()
+    }
+
+    pub fn by_id(&self) -> HashMap<MarketId, &MarketInfo> {
+        self.markets
+            .iter()
+            .map(|market| (market.id, market))
+            .collect()
+    }
+}
+
+fn scale_to_ui(value: f64, expo: i32) -> f64 {
+    value * 10f64.powi(expo)
+}
+
+fn bps_to_percent(bps: u16) -> f64 {
+    bps as f64 * 100.0 / BPS_DENOMINATOR as f64
+}
+
+/// Typed view of MarketInfo::status. Live markets report 1 and inactive ones 0. Any other value is
+/// kept as Unknown rather than guessed at, and is never treated as Active.
+#[derive(Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MarketStatus {
+    Inactive,
+    Active,
+    Unknown(u8),
+}
+
+impl From<u8> for MarketStatus {
+    fn from(status: u8) -> Self {
+        match status {
+            0 => Self::Inactive,
+            1 => Self::Active,
+            status => Self::Unknown(status),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct PriceFeedInfo {
     #[serde(with = "field_as_string")]
     pub price: u64,
+    #[serde(with = "number_or_string")]
     pub expo: i32,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct MarketInfoAccounting {
     #[serde(with = "field_as_string")]
     pub last_utilized_liquidation_capacity: u128,
@@ -330,14 +1045,81 @@ pub struct MarketInfoAccounting {
     #[serde(with = "field_as_string")]
     pub skew: i128,
     pub last_funding_rate: String,
-    pub last_funding_per_unit: String,
+    /// Fixed point with FUNDING_PER_UNIT_SCALE (1e18) precision.
+    #[serde(with = "field_as_string")]
+    pub last_funding_per_unit: i128,
     pub last_time_funding_updated: u64,
     pub first_liquidation_epoch_start_time: u64,
     pub last_liquidation_epoch_index: u64,
     pub last_time_liquidation_capacity_updated: u64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl MarketInfoAccounting {
+    /// Signed skew as a fraction of skew_scale. Returns 0 if skew_scale is 0.
+    pub fn utilization(&self, settings: &MarketInfoSettings) -> f64 {
+        if settings.skew_scale == 0 {
+            return 0.0;
+        }
+        self.skew as f64 / settings.skew_scale as f64
+    }
+
+    /// size is total open interest (long + short) and skew is long - short. Returns None if size
+    /// doesn't fit in i128 or size + skew overflows.
+    pub fn long_open_interest(&self) -> Option<u128> {
+        let long = i128::try_from(self.size).ok()?.checked_add(self.skew)?;
+        Some(long.max(0).unsigned_abs() / 2)
+    }
+
+    /// (size - skew) / 2, see long_open_interest.
+    pub fn short_open_interest(&self) -> Option<u128> {
+        let short = i128::try_from(self.size).ok()?.checked_sub(self.skew)?;
+        Some(short.max(0).unsigned_abs() / 2)
+    }
+
+    /// Annualized funding rate as a percentage. Assumes last_funding_rate is a daily rate with
+    /// FUNDING_RATE_SCALE (1e18) precision and that it stays constant for a 365 day year. Positive
+    /// rates mean longs pay shorts, negative rates mean shorts pay longs. Returns None if the rate
+    /// can't be parsed.
+    pub fn funding_rate_apr(&self) -> Option<f64> {
+        let daily_rate = self.last_funding_rate.parse::<f64>().ok()? / FUNDING_RATE_SCALE as f64;
+        Some(daily_rate * DAYS_PER_YEAR * 100.0)
+    }
+
+    /// Long open interest divided by short open interest. None if there is no short open interest
+    /// or either side can't be computed.
+    pub fn net_long_short_ratio(&self) -> Option<f64> {
+        let short_open_interest = self.short_open_interest()?;
+        if short_open_interest == 0 {
+            return None;
+        }
+        Some(self.long_open_interest()? as f64 / short_open_interest as f64)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl MarketInfoAccounting {
+    pub fn last_funding_updated_at(&self) -> DateTime<Utc> {
+        unix_seconds_to_date_time(self.last_time_funding_updated)
+    }
+
+    pub fn first_liquidation_epoch_started_at(&self) -> DateTime<Utc> {
+        unix_seconds_to_date_time(self.first_liquidation_epoch_start_time)
+    }
+
+    pub fn last_liquidation_capacity_updated_at(&self) -> DateTime<Utc> {
+        unix_seconds_to_date_time(self.last_time_liquidation_capacity_updated)
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn unix_seconds_to_date_time(seconds: u64) -> DateTime<Utc> {
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct MarketInfoSettings {
     #[serde(with = "field_as_string")]
     pub min_position_margin: u128,
@@ -347,41 +1129,128 @@ pub struct MarketInfoSettings {
     pub max_side_size: u128,
     pub max_liquidation_limit_accumulation_multiplier: u64, // bps
     pub max_seconds_in_liquidation_epoch: u64,
+    #[serde(with = "number_or_string")]
     pub initial_margin_ratio: u32,
+    #[serde(with = "number_or_string")]
     pub maker_fee_rate: u16,
+    #[serde(with = "number_or_string")]
     pub taker_fee_rate: u16,
+    #[serde(with = "number_or_string")]
     pub max_funding_velocity: u16,
+    #[serde(with = "number_or_string")]
     pub liquidation_fee_rate: u16,
+    #[serde(with = "number_or_string")]
     pub min_initial_margin_ratio: u16,
+    #[serde(with = "number_or_string")]
     pub maintenance_margin_proportion: u16,
+    #[serde(with = "number_or_string")]
     pub max_liquidation_pd: u16,
     #[serde(with = "field_as_string")]
     pub authorized_liquidator: Pubkey,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl MarketInfoSettings {
+    /// Fees round up so estimates never undercount what the protocol charges. Returns None if
+    /// notional * rate overflows u128.
+    pub fn maker_fee(&self, notional: u128) -> Option<u128> {
+        apply_fee_rate(notional, self.maker_fee_rate)
+    }
+
+    /// Rounds up and returns None on overflow, like maker_fee.
+    pub fn taker_fee(&self, notional: u128) -> Option<u128> {
+        apply_fee_rate(notional, self.taker_fee_rate)
+    }
+
+    /// Maker rate applies when size_delta reduces the magnitude of the market skew, taker rate otherwise.
+    pub fn trade_fee(&self, notional: u128, skew: i128, size_delta: i128) -> Option<u128> {
+        if skew.signum() * size_delta.signum() < 0 {
+            self.maker_fee(notional)
+        } else {
+            self.taker_fee(notional)
+        }
+    }
+}
+
+fn apply_fee_rate(notional: u128, fee_rate: u16) -> Option<u128> {
+    Some(
+        notional
+            .checked_mul(fee_rate as u128)?
+            .div_ceil(BPS_DENOMINATOR as u128),
+    )
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct ModifyPositionQuote {
+    /// Margin account the quote was computed for.
     #[serde(with = "field_as_string")]
     pub margin_account_address: Pubkey,
     pub margin_account_id: MarginAccountId,
     pub market_id: MarketId,
+    /// Requested change in position size.
     #[serde(with = "field_as_string")]
     pub size_delta: i128,
+    /// Worst fill price the trade will accept, derived from the slippage setting.
     #[serde(with = "field_as_string")]
     pub acceptable_price: u64,
+    /// Total margin the account must hold after the trade.
     #[serde(with = "field_as_string")]
     pub total_required_margin: u64,
+    /// Position size after the trade is applied.
     #[serde(with = "field_as_string")]
     pub new_position_size: i128,
+    /// Execution price including the skew premium/discount.
     #[serde(with = "field_as_string")]
     pub fill_price: u64,
-    pub last_interaction_funding_per_unit: String,
+    /// Market funding per unit at the time of the quote. Fixed point with FUNDING_PER_UNIT_SCALE (1e18)
+    /// precision.
+    #[serde(with = "field_as_string")]
+    pub last_interaction_funding_per_unit: i128,
+    /// Oracle index price the fill price is derived from.
     #[serde(with = "field_as_string")]
     pub index_price: u64,
+    /// Maker/taker fee charged for the trade in collateral units.
     #[serde(with = "field_as_string")]
     pub trade_fee: u64,
+    /// Initial margin ratio of the resulting position.
     #[serde(with = "field_as_string")]
     pub position_initial_margin_ratio: f64,
+    /// Maintenance margin ratio of the resulting position.
     #[serde(with = "field_as_string")]
     pub position_maintenance_margin_ratio: f64,
 }
+
+/// An lp position valued against the exchange's lp accounting at the time it was fetched. The api
+/// has no lp account route, so build this from lp account fields read from chain and get_exchange.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LpPositionInfo {
+    pub lp_shares: u64,
+    pub exchange_lp_balance: u64,
+    pub exchange_lp_shares: u64,
+    /// Unix timestamp after which the shares can be withdrawn.
+    pub unlocks_at: u64,
+}
+
+impl LpPositionInfo {
+    pub fn new(lp_shares: u64, last_add_liquidity_timestamp: u64, exchange: &ExchangeInfo) -> Self {
+        Self {
+            lp_shares,
+            exchange_lp_balance: exchange.accounting.lp_balance,
+            exchange_lp_shares: exchange.accounting.lp_shares,
+            unlocks_at: last_add_liquidity_timestamp
+                .saturating_add(exchange.settings.min_lp_duration),
+        }
+    }
+
+    /// Collateral value of the shares: lp_shares * exchange_lp_balance / exchange_lp_shares.
+    pub fn value(&self) -> u64 {
+        if self.exchange_lp_shares == 0 {
+            return 0;
+        }
+        (self.lp_shares as u128 * self.exchange_lp_balance as u128
+            / self.exchange_lp_shares as u128) as u64
+    }
+
+    pub fn is_locked(&self, now: u64) -> bool {
+        now < self.unlocks_at
+    }
+}
@@ -1,12 +1,28 @@
-use base64::{prelude::BASE64_STANDARD, Engine};
-use serde::{de, Deserialize, Deserializer};
+use base64::{
+    prelude::{BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD},
+    Engine,
+};
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+pub fn serialize<S>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&BASE64_STANDARD.encode(data))
+}
 
 pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s: String = String::deserialize(deserializer)?;
+    // Falls back to url-safe (padded or not) in case an endpoint encodes that way.
     BASE64_STANDARD
-        .decode(s)
+        .decode(&s)
+        .or_else(|err| {
+            BASE64_URL_SAFE_NO_PAD
+                .decode(s.trim_end_matches('='))
+                .map_err(|_| err)
+        })
         .map_err(|err| de::Error::custom(format!("Base64 decode error: {err:?}")))
 }
@@ -1,3 +1,5 @@
+// u128/i128 fields are always sent as strings. Plain json numbers are only used for values that fit
+// in u64/i64, which serde_json parses exactly, so arbitrary_precision is not needed.
 use serde::{de, Deserialize, Deserializer, Serializer};
 use std::str::FromStr;
 
@@ -16,6 +18,11 @@ where
     <T as FromStr>::Err: std::fmt::Debug,
 {
     let s: String = String::deserialize(deserializer)?;
-    s.parse()
+    let s = s.trim();
+    // Only a + directly followed by a digit is a sign, so "+-5" still fails to parse.
+    s.strip_prefix('+')
+        .filter(|unsigned| unsigned.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(s)
+        .parse()
         .map_err(|err| de::Error::custom(format!("Parse error: {err:?}")))
 }
@@ -1,5 +1,6 @@
 pub mod field_as_base64;
 pub mod field_as_string;
+pub mod number_or_string;
 pub mod optional_field_as_string;
 pub mod pubkey_values_map;
 pub mod pubkey_vec;
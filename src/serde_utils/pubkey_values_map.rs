@@ -1,8 +1,18 @@
 use crate::request::MarketId;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serializer};
 use solana_sdk::pubkey::Pubkey;
 use std::{collections::HashMap, str::FromStr};
 
+pub fn serialize<S>(data: &HashMap<MarketId, Pubkey>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_map(
+        data.iter()
+            .map(|(id, address)| (id.to_string(), address.to_string())),
+    )
+}
+
 pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<MarketId, Pubkey>, D::Error>
 where
     D: Deserializer<'de>,
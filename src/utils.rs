@@ -0,0 +1,171 @@
+use crate::{
+    constants::{
+        BPS_DENOMINATOR, EXCHANGE_SEED, FUNDING_PER_UNIT_SCALE, MARGIN_ACCOUNT_SEED,
+        PARCL_V3_PROGRAM_ID,
+    },
+    request::{ExchangeId, ExchangeIdentifier, MarginAccountId},
+    response::*,
+    ParclV3ApiClientError,
+};
+#[cfg(feature = "client")]
+use futures::{stream, StreamExt, TryStreamExt};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+/// |size| * price scaled by the price feed exponent. Returns None if the result overflows u128. A
+/// negative exponent too large for 10^|expo| to fit in u128 scales any notional down to 0.
+pub fn notional_value(size: i128, price_feed_info: &PriceFeedInfo) -> Option<u128> {
+    let notional = size
+        .unsigned_abs()
+        .checked_mul(price_feed_info.price as u128)?;
+    let scale = 10u128.checked_pow(price_feed_info.expo.unsigned_abs());
+    if price_feed_info.expo < 0 {
+        Some(scale.map_or(0, |scale| notional / scale))
+    } else {
+        notional.checked_mul(scale?)
+    }
+}
+
+/// Estimates the fee paid to a liquidator for the account's position in `market`.
+///
+/// fee = min(clamp(notional * liquidation_fee_rate / 10_000, min_liquidation_fee, max_liquidation_fee), required_liquidation_fee_margin)
+///
+/// where notional is the absolute position size at the market's current price. The fee is capped by the
+/// account's required_liquidation_fee_margin since an account can't pay out more than it has reserved.
+/// A notional that overflows saturates, so it ends up clamped to max_liquidation_fee.
+/// Returns 0 if the account has no position in `market`.
+pub fn estimate_liquidation_fee(
+    account: &MarginAccountInfo,
+    market: &MarketInfo,
+    exchange: &ExchangeInfo,
+) -> u64 {
+    let Some(position) = account
+        .positions
+        .iter()
+        .find(|position| position.market_id == market.id && position.size != 0)
+    else {
+        return 0;
+    };
+    let notional = notional_value(position.size, &market.price_feed_info).unwrap_or(u128::MAX);
+    let fee = notional.saturating_mul(market.settings.liquidation_fee_rate as u128)
+        / BPS_DENOMINATOR as u128;
+    let fee = u64::try_from(fee)
+        .unwrap_or(u64::MAX)
+        .max(exchange.settings.min_liquidation_fee)
+        .min(exchange.settings.max_liquidation_fee);
+    fee.min(account.margins.required_liquidation_fee_margin)
+}
+
+/// Funding accrued by the position since its last interaction, at the same precision as
+/// MarginAccountInfo::unrealized_pnl.
+///
+/// funding_owed = -size * (market.last_funding_per_unit - position.last_interaction_funding_per_unit) / 1e18
+///
+/// Positive values are owed by the position, negative values are owed to it. Returns None if the
+/// intermediate product overflows i128.
+pub fn funding_owed(position: &PositionInfo, market: &MarketInfo) -> Option<i128> {
+    let funding_per_unit_delta = market
+        .accounting
+        .last_funding_per_unit
+        .checked_sub(position.last_interaction_funding_per_unit)?;
+    position
+        .size
+        .checked_mul(funding_per_unit_delta)?
+        .checked_div(FUNDING_PER_UNIT_SCALE)?
+        .checked_neg()
+}
+
+pub fn get_exchange_address(exchange_id: ExchangeId) -> Pubkey {
+    Pubkey::find_program_address(
+        &[EXCHANGE_SEED, &exchange_id.to_le_bytes()],
+        &PARCL_V3_PROGRAM_ID,
+    )
+    .0
+}
+
+pub fn get_exchange_address_from_identifier(exchange_id: ExchangeIdentifier) -> Pubkey {
+    match exchange_id {
+        ExchangeIdentifier::Id(id) => get_exchange_address(id),
+        ExchangeIdentifier::Address(address) => address,
+    }
+}
+
+pub fn get_margin_account_address(
+    exchange: &Pubkey,
+    owner: &Pubkey,
+    margin_account_id: MarginAccountId,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            MARGIN_ACCOUNT_SEED,
+            exchange.as_ref(),
+            owner.as_ref(),
+            &margin_account_id.to_le_bytes(),
+        ],
+        &PARCL_V3_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Bincode-decodes a VersionedTransaction and resolves its compiled instructions against the
+/// message's static account keys. Errors if an instruction uses an address lookup table account.
+pub fn decode_transaction_instructions(tx_bytes: &[u8]) -> crate::Result<Vec<Instruction>> {
+    let message = bincode::deserialize::<VersionedTransaction>(tx_bytes)?.message;
+    let account_keys = message.static_account_keys();
+    let account_meta = |index: u8| {
+        account_keys
+            .get(index as usize)
+            .map(|pubkey| AccountMeta {
+                pubkey: *pubkey,
+                is_signer: message.is_signer(index as usize),
+                is_writable: message.is_maybe_writable(index as usize),
+            })
+            .ok_or(ParclV3ApiClientError::UnresolvedInstructionAccount(index))
+    };
+    message
+        .instructions()
+        .iter()
+        .map(|ix| {
+            Ok(Instruction {
+                program_id: account_meta(ix.program_id_index)?.pubkey,
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|index| account_meta(*index))
+                    .collect::<crate::Result<Vec<AccountMeta>>>()?,
+                data: ix.data.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Awaits fut and returns its output along with how long it took, e.g.
+/// `let (market, latency) = timed(client.get_market_from_id(23)).await;`
+pub async fn timed<T>(fut: impl Future<Output = T>) -> (T, Duration) {
+    let start = Instant::now();
+    let output = fut.await;
+    (output, start.elapsed())
+}
+
+/// Runs the futures with at most `limit` in flight and flattens the results, preserving input order.
+#[cfg(feature = "client")]
+pub(crate) async fn buffered_try_flatten<T, F>(
+    futures: impl IntoIterator<Item = F>,
+    limit: usize,
+) -> crate::Result<Vec<T>>
+where
+    F: Future<Output = crate::Result<Vec<T>>>,
+{
+    let results = stream::iter(futures)
+        .buffered(limit.max(1))
+        .try_collect::<Vec<Vec<T>>>()
+        .await?;
+    Ok(results.into_iter().flatten().collect())
+}
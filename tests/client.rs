@@ -0,0 +1,1251 @@
+#![cfg(feature = "client")]
+
+use parcl_v3_api_client::{
+    request::*,
+    response::*,
+    utils::{get_exchange_address, get_margin_account_address},
+    ParclV3ApiClient, ParclV3ApiClientConfig, ParclV3ApiClientError, RetryConfig,
+};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use wiremock::{
+    matchers::{body_partial_json, header, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn client(server: &MockServer) -> ParclV3ApiClient {
+    ParclV3ApiClient::from_url(server.uri())
+}
+
+fn exchange_json(address: Pubkey) -> Value {
+    json!({
+        "address": address.to_string(),
+        "accounting": {
+            "notional_open_interest": "340282366920938463463374607431768211455",
+            "last_time_locked_open_interest_accounting_refreshed": "1700000000",
+            "balance": "1000000000",
+            "margin_balance": "600000000",
+            "lp_balance": "400000000",
+            "lp_shares": "390000000",
+            "protocol_fees": "12345",
+            "unsettled_collateral_amount": "0"
+        },
+        "settings": {
+            "min_lp_duration": 86400,
+            "settlement_delay": 3600,
+            "min_liquidation_fee": "1000000",
+            "max_liquidation_fee": "100000000",
+            "locked_open_interest_staleness_threshold": 60,
+            "protocol_fee_rate": 1000,
+            "locked_open_interest_ratio": 5000,
+            "max_keeper_tip_rate": 50
+        },
+        "id": "0",
+        "market_ids": [23, 24],
+        "oracle_configs": [
+            { "kind": "pyth-v2", "program_id": Pubkey::new_unique().to_string() }
+        ],
+        "status": 1,
+        "collateral_expo": -6,
+        "collateral_mint": Pubkey::new_unique().to_string(),
+        "collateral_vault": Pubkey::new_unique().to_string(),
+        "admin": Pubkey::new_unique().to_string(),
+        "nominated_admin": Pubkey::new_unique().to_string(),
+        "authorized_settler": Pubkey::new_unique().to_string(),
+        "authorized_protocol_fees_collector": Pubkey::new_unique().to_string()
+    })
+}
+
+fn market_json(address: Pubkey, id: MarketId) -> Value {
+    json!({
+        "address": address.to_string(),
+        "price_feed_info": { "price": "150250000", "expo": -6 },
+        "accounting": {
+            "last_utilized_liquidation_capacity": "0",
+            "size": "5000000",
+            "skew": "-1000000",
+            "last_funding_rate": "-1500000000000000",
+            "last_funding_per_unit": "-42000000000000000000",
+            "last_time_funding_updated": 1700000000u64,
+            "first_liquidation_epoch_start_time": 1690000000u64,
+            "last_liquidation_epoch_index": 3,
+            "last_time_liquidation_capacity_updated": 1700000100u64
+        },
+        "settings": {
+            "min_position_margin": "5000000",
+            "skew_scale": "1000000000000",
+            "max_side_size": "10000000000",
+            "max_liquidation_limit_accumulation_multiplier": 20000,
+            "max_seconds_in_liquidation_epoch": 30,
+            "initial_margin_ratio": 1000,
+            "maker_fee_rate": 5,
+            "taker_fee_rate": 10,
+            "max_funding_velocity": 9,
+            "liquidation_fee_rate": 50,
+            "min_initial_margin_ratio": 500,
+            "maintenance_margin_proportion": 5000,
+            "max_liquidation_pd": 30,
+            "authorized_liquidator": Pubkey::default().to_string()
+        },
+        "id": id,
+        "exchange": Pubkey::new_unique().to_string(),
+        "price_feed": Pubkey::new_unique().to_string(),
+        "status": 1
+    })
+}
+
+fn margin_account_json(address: Pubkey, owner: Pubkey) -> Value {
+    json!({
+        "address": address.to_string(),
+        "id": 0,
+        "active_market_ids": [23],
+        "positions": [
+            {
+                "size": "-20000000",
+                "last_interaction_price": "149000000",
+                "last_interaction_funding_per_unit": "-41000000000000000000",
+                "market_id": 23
+            }
+        ],
+        "margins": {
+            "available_margin": "-5000",
+            "total_required_margin": "3000000",
+            "required_initial_margin": "2000000",
+            "required_maintenance_margin": "1000000",
+            "required_liquidation_fee_margin": "1000000",
+            "accumulated_liquidation_fees": "0"
+        },
+        "margin": "10000000",
+        "excess_margin": "7000000",
+        "exchange": Pubkey::new_unique().to_string(),
+        "owner": owner.to_string(),
+        "delegate": Pubkey::default().to_string(),
+        "can_close": false,
+        "can_liquidate": false,
+        "in_liquidation": false
+    })
+}
+
+#[tokio::test]
+async fn get_exchange_deserializes_string_fields() {
+    let server = MockServer::start().await;
+    let address = Pubkey::new_unique();
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .and(query_param("exchange_id", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(exchange_json(address)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let exchange = client(&server).get_exchange().await.unwrap();
+    assert_eq!(exchange.address, address);
+    assert_eq!(exchange.id, 0);
+    assert_eq!(exchange.accounting.notional_open_interest, u128::MAX);
+    assert_eq!(
+        exchange
+            .accounting
+            .last_time_locked_open_interest_accounting_refreshed,
+        1_700_000_000
+    );
+    assert_eq!(exchange.settings.max_liquidation_fee, 100_000_000);
+    assert_eq!(exchange.market_ids, vec![23, 24]);
+    assert_eq!(exchange.oracle_configs[0].kind, OracleKind::PythV2);
+}
+
+#[tokio::test]
+async fn get_market_deserializes_string_fields() {
+    let server = MockServer::start().await;
+    let address = Pubkey::new_unique();
+    Mock::given(method("GET"))
+        .and(path("/market"))
+        .and(query_param("market_id", "23"))
+        .and(query_param("exchange_id", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(market_json(address, 23)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let market = client(&server).get_market_from_id(23).await.unwrap();
+    assert_eq!(market.address, address);
+    assert_eq!(market.id, 23);
+    assert_eq!(market.price_feed_info.price, 150_250_000);
+    assert_eq!(market.price_feed_info.expo, -6);
+    assert_eq!(market.accounting.size, 5_000_000);
+    assert_eq!(market.accounting.skew, -1_000_000);
+    assert_eq!(
+        market.accounting.last_funding_per_unit,
+        -42_000_000_000_000_000_000
+    );
+    assert_eq!(market.settings.skew_scale, 1_000_000_000_000);
+    assert_eq!(market.settings.authorized_liquidator, Pubkey::default());
+}
+
+#[tokio::test]
+async fn get_margin_account_deserializes_string_fields() {
+    let server = MockServer::start().await;
+    let address = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    Mock::given(method("GET"))
+        .and(path("/margin-account"))
+        .and(query_param("margin_account_id", "0"))
+        .and(query_param("owner", owner.to_string()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(margin_account_json(address, owner)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let margin_account = client(&server)
+        .get_margin_account_from_id(owner, 0)
+        .await
+        .unwrap();
+    assert_eq!(margin_account.address, address);
+    assert_eq!(margin_account.owner, owner);
+    assert_eq!(margin_account.margin, 10_000_000);
+    assert_eq!(margin_account.margins.available_margin, -5_000);
+    assert_eq!(margin_account.positions[0].size, -20_000_000);
+    assert_eq!(
+        margin_account.positions[0].last_interaction_price,
+        149_000_000
+    );
+    assert_eq!(margin_account.position_size(23), -20_000_000);
+}
+
+fn assert_round_trip<T: serde::de::DeserializeOwned + serde::Serialize>(fixture: Value) {
+    let deserialized = serde_json::from_value::<T>(fixture.clone()).unwrap();
+    assert_eq!(serde_json::to_value(deserialized).unwrap(), fixture);
+}
+
+#[test]
+fn responses_round_trip_through_json() {
+    let address = Pubkey::new_unique();
+    assert_round_trip::<ExchangeInfo>(exchange_json(address));
+    assert_round_trip::<MarketInfo>(market_json(address, 23));
+    assert_round_trip::<MarginAccountInfo>(margin_account_json(address, Pubkey::new_unique()));
+}
+
+#[tokio::test]
+async fn error_status_is_surfaced() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/margin-account"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("margin account not found"))
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let err = client
+        .get_margin_account_from_address(Pubkey::new_unique())
+        .await
+        .unwrap_err();
+    match err {
+        ParclV3ApiClientError::Request(status, body) => {
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(body, "margin account not found");
+        }
+        _ => panic!("unexpected error: {err:?}"),
+    }
+    assert!(client
+        .get_margin_account_opt(MarginAccountIdentifier::Address(Pubkey::new_unique()), None)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn empty_market_addresses_are_not_mistaken_for_ids() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/market-ids"))
+        .and(query_param("response_kind", "addresses"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&server)
+        .await;
+
+    assert!(client(&server)
+        .get_market_addresses()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn get_market_cached_reuses_fresh_entries() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/market"))
+        .and(query_param("market_id", "23"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(market_json(Pubkey::new_unique(), 23)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let ttl = std::time::Duration::from_secs(60);
+    let first = client.get_market_cached(23, ttl).await.unwrap();
+    let second = client.clone().get_market_cached(23, ttl).await.unwrap();
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn get_market_cached_shares_concurrent_misses() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/market"))
+        .and(query_param("market_id", "23"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(market_json(Pubkey::new_unique(), 23))
+                .set_delay(std::time::Duration::from_millis(50)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let ttl = std::time::Duration::from_secs(60);
+    let (first, second) = tokio::join!(
+        client.get_market_cached(23, ttl),
+        client.get_market_cached(23, ttl)
+    );
+    assert_eq!(first.unwrap(), second.unwrap());
+}
+
+#[tokio::test]
+async fn markets_by_status_keeps_unknown_statuses_separate() {
+    let server = MockServer::start().await;
+    let mut unknown = market_json(Pubkey::new_unique(), 24);
+    unknown["status"] = json!(7);
+    Mock::given(method("GET"))
+        .and(path("/market-ids"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([23, 24])))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/markets"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([market_json(Pubkey::new_unique(), 23), unknown])),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let active = client
+        .get_markets_by_status(MarketStatus::Active)
+        .await
+        .unwrap();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].id, 23);
+    let unknown = client
+        .get_markets_by_status(MarketStatus::Unknown(7))
+        .await
+        .unwrap();
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].market_status(), MarketStatus::Unknown(7));
+}
+
+#[tokio::test]
+async fn priority_fee_above_cap_is_rejected() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/close-margin-account-transaction"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "transaction": "",
+            "total_required_lamports": 10_000,
+            "required_compute_lamports": 5_000,
+            "required_rent_lamports": 5_000,
+            "cu_limit": 200_000
+        })))
+        .mount(&server)
+        .await;
+
+    let client = ParclV3ApiClient::new(ParclV3ApiClientConfig {
+        base_url: server.uri(),
+        max_priority_fee_lamports: Some(1_000),
+        ..Default::default()
+    });
+    let err = client
+        .get_close_margin_account_transaction(
+            Pubkey::new_unique(),
+            MarginAccountIdentifier::Id(0),
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ParclV3ApiClientError::PriorityFeeExceedsCap(5_000, 1_000)
+    ));
+}
+
+#[tokio::test]
+async fn unhealthy_account_stream_yields_new_accounts() {
+    use futures::StreamExt;
+
+    let server = MockServer::start().await;
+    let first = Pubkey::new_unique();
+    let second = Pubkey::new_unique();
+    Mock::given(method("GET"))
+        .and(path("/unhealthy-margin-accounts"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([first.to_string()])))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/unhealthy-margin-accounts"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([first.to_string(), second.to_string()])),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let stream = client.unhealthy_account_stream(std::time::Duration::from_millis(10));
+    futures::pin_mut!(stream);
+    assert_eq!(stream.next().await.unwrap().unwrap(), vec![first]);
+    assert_eq!(stream.next().await.unwrap().unwrap(), vec![second]);
+}
+
+#[tokio::test]
+async fn default_owner_is_rejected_before_sending() {
+    let server = MockServer::start().await;
+    let err = client(&server)
+        .get_margin_account_from_id(Pubkey::default(), 0)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ParclV3ApiClientError::InvalidInput(_)));
+    let err = client(&server)
+        .get_margin_accounts(&[Pubkey::new_unique(), Pubkey::default()])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ParclV3ApiClientError::InvalidInput(_)));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn default_address_identifiers_are_rejected_before_sending() {
+    let server = MockServer::start().await;
+    let client = client(&server);
+    let default_margin_account = MarginAccountIdentifier::Address(Pubkey::default());
+    let errs = [
+        client
+            .get_margin_account(default_margin_account, None)
+            .await
+            .unwrap_err(),
+        client
+            .get_deposit_margin_transaction(Pubkey::new_unique(), default_margin_account, 1, None)
+            .await
+            .unwrap_err(),
+        client
+            .get_liquidate_instructions(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                default_margin_account,
+                None,
+            )
+            .await
+            .unwrap_err(),
+        client
+            .get_markets_from_addresses(&[Pubkey::new_unique(), Pubkey::default()])
+            .await
+            .unwrap_err(),
+    ];
+    for err in errs {
+        assert!(matches!(err, ParclV3ApiClientError::InvalidInput(_)));
+    }
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn margin_account_opt_from_id_fetches_derived_address() {
+    let server = MockServer::start().await;
+    let owner = Pubkey::new_unique();
+    let address = get_margin_account_address(&get_exchange_address(0), &owner, 3);
+    Mock::given(method("GET"))
+        .and(path("/margin-account"))
+        .and(query_param("margin_account_id", address.to_string()))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(client(&server)
+        .get_margin_account_opt_from_id(owner, 3)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn api_version_is_sent_and_mismatch_is_surfaced() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .and(header(
+            "Accept",
+            "application/vnd.parcl.v3+json; version=2024-01-01",
+        ))
+        .respond_with(ResponseTemplate::new(406).set_body_string("unsupported version"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = ParclV3ApiClient::new(ParclV3ApiClientConfig {
+        base_url: server.uri(),
+        api_version: Some("2024-01-01".to_string()),
+        ..Default::default()
+    });
+    let err = client.get_exchange().await.unwrap_err();
+    assert!(
+        matches!(&err, ParclV3ApiClientError::VersionMismatch(body) if body == "unsupported version")
+    );
+}
+
+#[tokio::test]
+async fn not_acceptable_without_api_version_is_a_request_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .respond_with(ResponseTemplate::new(406))
+        .mount(&server)
+        .await;
+
+    assert!(matches!(
+        client(&server).get_exchange().await,
+        Err(ParclV3ApiClientError::Request(
+            StatusCode::NOT_ACCEPTABLE,
+            _
+        ))
+    ));
+}
+
+#[test]
+fn invalid_api_version_is_rejected_by_try_new() {
+    let result = ParclV3ApiClient::try_new(ParclV3ApiClientConfig {
+        api_version: Some("2024-01-01\n".to_string()),
+        ..Default::default()
+    });
+    assert!(matches!(
+        result,
+        Err(ParclV3ApiClientError::InvalidInput(_))
+    ));
+}
+
+#[tokio::test]
+async fn margin_accounts_map_omits_missing_accounts() {
+    let server = MockServer::start().await;
+    let (present, missing) = (Pubkey::new_unique(), Pubkey::new_unique());
+    Mock::given(method("POST"))
+        .and(path("/margin-accounts"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            margin_account_json(present, Pubkey::new_unique()),
+            null
+        ])))
+        .mount(&server)
+        .await;
+
+    let margin_accounts = client(&server)
+        .get_margin_accounts_map(&[present, missing])
+        .await
+        .unwrap();
+    assert_eq!(margin_accounts.len(), 1);
+    assert_eq!(margin_accounts[&present].address, present);
+}
+
+#[tokio::test]
+async fn markets_from_ids_follow_input_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/markets"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            market_json(Pubkey::new_unique(), 7),
+            market_json(Pubkey::new_unique(), 23),
+            market_json(Pubkey::new_unique(), 2),
+        ])))
+        .mount(&server)
+        .await;
+
+    let markets = client(&server)
+        .get_markets_from_ids(&[23, 2, 7])
+        .await
+        .unwrap();
+    let ids = markets.iter().map(|market| market.id).collect::<Vec<_>>();
+    assert_eq!(ids, vec![23, 2, 7]);
+}
+
+#[tokio::test]
+async fn get_exchange_cached_reuses_value_on_not_modified() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(exchange_json(Pubkey::new_unique())),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let first = client.get_exchange_cached().await.unwrap();
+    let second = client.get_exchange_cached().await.unwrap();
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn get_exchange_cached_refetches_on_not_modified_without_cache() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .respond_with(ResponseTemplate::new(304))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(exchange_json(Pubkey::new_unique())),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client(&server).get_exchange_cached().await.unwrap();
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests
+        .iter()
+        .all(|request| !request.headers.contains_key("If-None-Match")));
+}
+
+#[test]
+fn error_kind_classifies_request_statuses() {
+    use parcl_v3_api_client::ErrorKind;
+
+    let kind = |status| ParclV3ApiClientError::Request(status, String::new()).kind();
+    assert_eq!(kind(StatusCode::TOO_MANY_REQUESTS), ErrorKind::RateLimited);
+    assert_eq!(kind(StatusCode::NOT_FOUND), ErrorKind::NotFound);
+    assert_eq!(kind(StatusCode::BAD_GATEWAY), ErrorKind::ServerError);
+    assert_eq!(kind(StatusCode::BAD_REQUEST), ErrorKind::InvalidInput);
+    assert_eq!(
+        kind(StatusCode::UNPROCESSABLE_ENTITY),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(kind(StatusCode::UNAUTHORIZED), ErrorKind::Unauthorized);
+    assert_eq!(kind(StatusCode::FORBIDDEN), ErrorKind::Unauthorized);
+    assert_eq!(kind(StatusCode::CONFLICT), ErrorKind::Other);
+    assert_eq!(
+        ParclV3ApiClientError::from(serde_json::from_str::<u64>("x").unwrap_err()).kind(),
+        ErrorKind::Deserialize
+    );
+}
+
+#[tokio::test]
+async fn connection_failure_is_a_network_error() {
+    let client = ParclV3ApiClient::from_url("http://127.0.0.1:1");
+    let err = client.get_exchange().await.unwrap_err();
+    assert!(matches!(err, ParclV3ApiClientError::Network(_)));
+}
+
+#[tokio::test]
+async fn invalid_base_url_is_an_invalid_input_error() {
+    use parcl_v3_api_client::ErrorKind;
+
+    let client = ParclV3ApiClient::from_url("not a url");
+    let err = client.get_exchange().await.unwrap_err();
+    assert!(matches!(err, ParclV3ApiClientError::Network(_)));
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[tokio::test]
+async fn try_get_markets_keeps_per_id_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/market"))
+        .and(query_param("market_id", "23"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(market_json(Pubkey::new_unique(), 23)),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/market"))
+        .and(query_param("market_id", "99"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let markets = client(&server)
+        .try_get_markets(&[MarketIdentifier::Id(23), MarketIdentifier::Id(99)])
+        .await
+        .unwrap();
+    assert_eq!(markets[0].as_ref().unwrap().id, 23);
+    assert!(matches!(
+        markets[1],
+        Err(ParclV3ApiClientError::Request(StatusCode::NOT_FOUND, _))
+    ));
+}
+
+#[test]
+fn liquidation_price_solves_for_maintenance_margin() {
+    let mut margin_account_json = margin_account_json(Pubkey::new_unique(), Pubkey::new_unique());
+    margin_account_json["positions"][0]["size"] = json!("-20000");
+    let mut margin_account =
+        serde_json::from_value::<MarginAccountInfo>(margin_account_json).unwrap();
+    let market =
+        serde_json::from_value::<MarketInfo>(market_json(Pubkey::new_unique(), 23)).unwrap();
+    let exchange =
+        serde_json::from_value::<ExchangeInfo>(exchange_json(Pubkey::new_unique())).unwrap();
+
+    // Short 0.02 at 149 with 9 of margin above the liquidation fee and a 5% maintenance ratio.
+    assert_eq!(
+        margin_account.liquidation_price(&market, &exchange),
+        Some(570_476_190)
+    );
+    margin_account.positions[0].size = 0;
+    assert_eq!(margin_account.liquidation_price(&market, &exchange), None);
+}
+
+#[tokio::test]
+async fn server_errors_are_retried_within_max_elapsed() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(exchange_json(Pubkey::new_unique())))
+        .mount(&server)
+        .await;
+
+    let retry = RetryConfig {
+        max_retries: 3,
+        initial_backoff: std::time::Duration::from_millis(10),
+        jitter: true,
+        ..Default::default()
+    };
+    let client = ParclV3ApiClient::new(ParclV3ApiClientConfig {
+        base_url: server.uri(),
+        retry: retry.clone(),
+        ..Default::default()
+    });
+    assert!(client.get_exchange().await.is_ok());
+
+    server.reset().await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = ParclV3ApiClient::new(ParclV3ApiClientConfig {
+        base_url: server.uri(),
+        retry: RetryConfig {
+            initial_backoff: std::time::Duration::from_secs(1),
+            max_elapsed: Some(std::time::Duration::from_millis(100)),
+            jitter: false,
+            ..retry
+        },
+        ..Default::default()
+    });
+    assert!(matches!(
+        client.get_exchange().await,
+        Err(ParclV3ApiClientError::Request(
+            StatusCode::SERVICE_UNAVAILABLE,
+            _
+        ))
+    ));
+}
+
+#[test]
+fn display_table_scales_market_fields() {
+    let market =
+        serde_json::from_value::<MarketInfo>(market_json(Pubkey::new_unique(), 23)).unwrap();
+    let table = market.display_table(-6);
+    assert!(table.contains("price             150.25"));
+    assert!(table.contains("skew              -1"));
+    assert!(table.contains("taker fee         0.10%"));
+}
+
+#[tokio::test]
+async fn priority_fee_percentile_argument_overrides_client_default() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/deposit-margin-transaction"))
+        .and(body_partial_json(json!({ "priority_fee_percentile": 90 })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "transaction": "",
+            "total_required_lamports": 0,
+            "required_compute_lamports": 0,
+            "required_rent_lamports": 0,
+            "cu_limit": 0
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/deposit-margin-transaction"))
+        .and(body_partial_json(json!({ "priority_fee_percentile": 50 })))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = ParclV3ApiClient::new(ParclV3ApiClientConfig {
+        base_url: server.uri(),
+        priority_fee_percentile: Some(50),
+        ..Default::default()
+    });
+    let owner = Pubkey::new_unique();
+    client
+        .get_deposit_margin_transaction(owner, MarginAccountIdentifier::Id(0), 1, Some(90))
+        .await
+        .unwrap();
+    assert!(client
+        .get_deposit_margin_transaction(owner, MarginAccountIdentifier::Id(0), 1, None)
+        .await
+        .is_err());
+}
+
+#[test]
+fn client_is_shareable_across_tasks() {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+    assert_send_sync::<ParclV3ApiClient>();
+    assert_send_sync::<Box<dyn parcl_v3_api_client::api::ParclV3Api>>();
+}
+
+#[tokio::test]
+async fn exchange_identifier_matches_in_query_and_body() {
+    let server = MockServer::start().await;
+    let exchange = Pubkey::new_unique();
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .and(query_param("exchange_id", exchange.to_string()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(exchange_json(exchange)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/modify-position-quote"))
+        .and(body_partial_json(
+            json!({ "exchange_id": exchange.to_string() }),
+        ))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = ParclV3ApiClient::new(ParclV3ApiClientConfig {
+        base_url: server.uri(),
+        exchange_id: Some(ExchangeIdentifier::Address(exchange)),
+        ..Default::default()
+    });
+    client.get_exchange().await.unwrap();
+    assert!(client
+        .get_modify_position_quote(
+            Pubkey::new_unique(),
+            MarginAccountIdentifier::Id(0),
+            23,
+            1,
+            SlippageSetting::SlippageToleranceBps(100),
+        )
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn close_position_quote_negates_position_size() {
+    let server = MockServer::start().await;
+    let owner = Pubkey::new_unique();
+    Mock::given(method("GET"))
+        .and(path("/margin-account"))
+        .and(query_param("owner", owner.to_string()))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(margin_account_json(Pubkey::new_unique(), owner)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/modify-position-quote"))
+        .and(body_partial_json(json!({
+            "owner": owner.to_string(),
+            "market_id": 23,
+            "size_delta": "20000000",
+        })))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(client(&server)
+        .get_close_position_quote(
+            owner,
+            MarginAccountIdentifier::Id(0),
+            23,
+            SlippageSetting::SlippageToleranceBps(100),
+        )
+        .await
+        .is_err());
+}
+
+#[test]
+fn open_interest_splits_size_by_skew() {
+    let mut accounting =
+        serde_json::from_value::<MarketInfo>(market_json(Pubkey::new_unique(), 23))
+            .unwrap()
+            .accounting;
+    // Short skew: size 5_000_000, skew -1_000_000.
+    assert_eq!(accounting.long_open_interest(), Some(2_000_000));
+    assert_eq!(accounting.short_open_interest(), Some(3_000_000));
+    assert_eq!(accounting.net_long_short_ratio(), Some(2.0 / 3.0));
+
+    accounting.skew = 1_000_000;
+    assert_eq!(accounting.long_open_interest(), Some(3_000_000));
+    assert_eq!(accounting.short_open_interest(), Some(2_000_000));
+    assert_eq!(accounting.net_long_short_ratio(), Some(1.5));
+
+    accounting.skew = 0;
+    assert_eq!(accounting.long_open_interest(), Some(2_500_000));
+    assert_eq!(accounting.short_open_interest(), Some(2_500_000));
+    assert_eq!(accounting.net_long_short_ratio(), Some(1.0));
+
+    accounting.size = u128::MAX;
+    assert_eq!(accounting.long_open_interest(), None);
+    assert_eq!(accounting.net_long_short_ratio(), None);
+}
+
+#[test]
+fn funding_rate_apr_annualizes_daily_rate() {
+    let mut accounting =
+        serde_json::from_value::<MarketInfo>(market_json(Pubkey::new_unique(), 23))
+            .unwrap()
+            .accounting;
+    let apr = |accounting: &MarketInfoAccounting| accounting.funding_rate_apr().unwrap();
+
+    // -0.0015 daily * 365 days, as a percentage.
+    assert!((apr(&accounting) + 54.75).abs() < 1e-9);
+    accounting.last_funding_rate = "2000000000000000".to_string();
+    assert!((apr(&accounting) - 73.0).abs() < 1e-9);
+    accounting.last_funding_rate = "not a number".to_string();
+    assert_eq!(accounting.funding_rate_apr(), None);
+}
+
+#[test]
+fn liquidation_status_compares_available_and_maintenance_margin() {
+    let mut margin_account = serde_json::from_value::<MarginAccountInfo>(margin_account_json(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ))
+    .unwrap();
+    assert_eq!(
+        margin_account.liquidation_status(),
+        LiquidationStatus::Healthy
+    );
+
+    margin_account.can_liquidate = true;
+    assert_eq!(
+        margin_account.liquidation_status(),
+        LiquidationStatus::BelowMaintenanceMargin {
+            available_margin: -5_000,
+            required_maintenance_margin: 1_000_000,
+        }
+    );
+
+    margin_account.margins.available_margin = 1_000_000;
+    let status = margin_account.liquidation_status();
+    assert_eq!(status, LiquidationStatus::Liquidatable);
+    assert!(status.is_liquidatable());
+
+    margin_account.in_liquidation = true;
+    assert_eq!(
+        margin_account.liquidation_status(),
+        LiquidationStatus::InLiquidation
+    );
+
+    margin_account.in_liquidation = false;
+    margin_account.can_liquidate = false;
+    margin_account.positions[0].size = 0;
+    assert_eq!(
+        margin_account.liquidation_status(),
+        LiquidationStatus::NoPositions
+    );
+}
+
+#[test]
+fn total_notional_and_leverage() {
+    let mut margin_account = serde_json::from_value::<MarginAccountInfo>(margin_account_json(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ))
+    .unwrap();
+    margin_account.positions.push(PositionInfo {
+        size: 5,
+        last_interaction_price: 0,
+        last_interaction_funding_per_unit: 0,
+        market_id: 24,
+    });
+    let prices = [(23, 2), (24, 4_000_000)].into_iter().collect();
+    assert_eq!(margin_account.total_notional(&prices), 60_000_000);
+    assert_eq!(margin_account.leverage(&prices), Some(6.0));
+}
+
+#[test]
+fn unrealized_pnl_is_none_on_overflow() {
+    let mut margin_account = serde_json::from_value::<MarginAccountInfo>(margin_account_json(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ))
+    .unwrap();
+    // Short 20 at 149 marked at 150.
+    let prices = [(23, 150_000_000)].into_iter().collect();
+    assert_eq!(
+        margin_account.unrealized_pnl(&prices),
+        Some(-20_000_000_000_000)
+    );
+    assert_eq!(
+        margin_account.try_unrealized_pnl(&prices).unwrap(),
+        Some(-20_000_000_000_000)
+    );
+    margin_account.positions[0].size = i128::MIN;
+    assert_eq!(margin_account.unrealized_pnl(&prices), None);
+    assert!(matches!(
+        margin_account.try_unrealized_pnl(&Default::default()),
+        Err(ParclV3ApiClientError::MissingMarketPrice(23))
+    ));
+}
+
+#[test]
+fn lp_position_values_shares_from_exchange_accounting() {
+    let exchange =
+        serde_json::from_value::<ExchangeInfo>(exchange_json(Pubkey::new_unique())).unwrap();
+
+    let lp_position = LpPositionInfo::new(39_000_000, 1_700_000_000, &exchange);
+    // 39_000_000 * 400_000_000 / 390_000_000
+    assert_eq!(lp_position.value(), 40_000_000);
+    assert_eq!(lp_position.unlocks_at, 1_700_086_400);
+    assert!(lp_position.is_locked(1_700_000_001));
+    assert!(!lp_position.is_locked(1_700_086_400));
+}
+
+#[tokio::test]
+async fn close_all_positions_sends_distinct_idempotency_keys() {
+    let server = MockServer::start().await;
+    let owner = Pubkey::new_unique();
+    let mut margin_account = margin_account_json(Pubkey::new_unique(), owner);
+    margin_account["active_market_ids"] = json!([23, 24]);
+    Mock::given(method("GET"))
+        .and(path("/margin-account"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(margin_account))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/close-position-instructions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "instructions": { "v3_instructions": [], "compute_budget_instructions": [] },
+            "total_required_lamports": 0,
+            "required_compute_lamports": 0,
+            "required_rent_lamports": 0,
+            "cu_limit": 0
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    client(&server)
+        .with_idempotency_key("close-all")
+        .get_close_all_positions_instructions(
+            owner,
+            MarginAccountIdentifier::Id(0),
+            SlippageSetting::SlippageToleranceBps(100),
+            None,
+        )
+        .await
+        .unwrap();
+    let mut idempotency_keys = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter_map(|request| request.headers.get("Idempotency-Key"))
+        .map(|key| key.to_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    idempotency_keys.sort();
+    assert_eq!(idempotency_keys, vec!["close-all-23", "close-all-24"]);
+}
+
+#[tokio::test]
+async fn market_ids_are_paged_client_side() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/market-ids"))
+        .and(query_param("response_kind", "ids"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([1, 2, 3, 4, 5])))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let page = client
+        .get_market_ids_paged(PageParams {
+            limit: Some(2),
+            offset: Some(1),
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.market_ids, vec![2, 3]);
+    assert_eq!(page.next_offset, Some(3));
+    let page = client
+        .get_market_ids_paged(PageParams {
+            limit: Some(2),
+            offset: page.next_offset,
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.market_ids, vec![4, 5]);
+    assert_eq!(page.next_offset, None);
+}
+
+#[test]
+fn funding_owed_is_none_on_overflow() {
+    use parcl_v3_api_client::utils::funding_owed;
+
+    let mut margin_account = serde_json::from_value::<MarginAccountInfo>(margin_account_json(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ))
+    .unwrap();
+    let market =
+        serde_json::from_value::<MarketInfo>(market_json(Pubkey::new_unique(), 23)).unwrap();
+    assert_eq!(
+        funding_owed(&margin_account.positions[0], &market),
+        Some(-20_000_000)
+    );
+    margin_account.positions[0].size = i128::MAX / 2;
+    assert_eq!(funding_owed(&margin_account.positions[0], &market), None);
+}
+
+#[test]
+fn notional_value_handles_extreme_exponents() {
+    use parcl_v3_api_client::utils::notional_value;
+
+    let price_feed_info = |expo| PriceFeedInfo {
+        price: 150_250_000,
+        expo,
+    };
+    assert_eq!(
+        notional_value(-20_000_000, &price_feed_info(-6)),
+        Some(3_005_000_000)
+    );
+    assert_eq!(notional_value(1, &price_feed_info(-40)), Some(0));
+    assert_eq!(notional_value(1, &price_feed_info(40)), None);
+    assert_eq!(notional_value(i128::MAX, &price_feed_info(0)), None);
+}
+
+#[test]
+fn estimate_liquidation_fee_clamps_and_caps() {
+    use parcl_v3_api_client::utils::estimate_liquidation_fee;
+
+    let mut margin_account = serde_json::from_value::<MarginAccountInfo>(margin_account_json(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ))
+    .unwrap();
+    let market =
+        serde_json::from_value::<MarketInfo>(market_json(Pubkey::new_unique(), 23)).unwrap();
+    let exchange =
+        serde_json::from_value::<ExchangeInfo>(exchange_json(Pubkey::new_unique())).unwrap();
+    let fee = |margin_account: &MarginAccountInfo| {
+        estimate_liquidation_fee(margin_account, &market, &exchange)
+    };
+
+    // 3_005_000_000 notional * 50 bps, capped at required_liquidation_fee_margin.
+    assert_eq!(fee(&margin_account), 1_000_000);
+    margin_account.margins.required_liquidation_fee_margin = u64::MAX;
+    assert_eq!(fee(&margin_account), 15_025_000);
+    margin_account.positions[0].size = -1;
+    assert_eq!(fee(&margin_account), exchange.settings.min_liquidation_fee);
+    margin_account.positions[0].size = i128::MIN;
+    assert_eq!(fee(&margin_account), exchange.settings.max_liquidation_fee);
+}
+
+#[tokio::test]
+async fn base_url_with_or_without_trailing_slash_joins_with_one_slash() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/exchange"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(exchange_json(Pubkey::new_unique())))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/parcl/v3/exchange"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(exchange_json(Pubkey::new_unique())))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    for base_url in [
+        server.uri(),
+        format!("{}/", server.uri()),
+        format!("{}/parcl/v3", server.uri()),
+        format!("{}/parcl/v3/", server.uri()),
+    ] {
+        ParclV3ApiClient::from_url(base_url)
+            .get_exchange()
+            .await
+            .unwrap();
+    }
+    for request in server.received_requests().await.unwrap() {
+        assert!(!request.url.path().contains("//"));
+    }
+}
+
+#[tokio::test]
+async fn dry_run_returns_the_built_request_without_sending() {
+    let server = MockServer::start().await;
+    let owner = Pubkey::new_unique();
+    let request = client(&server)
+        .dry_run(|client| async move {
+            client
+                .get_deposit_margin_transaction(owner, MarginAccountIdentifier::Id(0), 1, None)
+                .await
+        })
+        .await
+        .unwrap();
+    assert_eq!(request.method(), reqwest::Method::POST);
+    assert_eq!(request.url().path(), "/deposit-margin-transaction");
+    let body =
+        serde_json::from_slice::<Value>(request.body().unwrap().as_bytes().unwrap()).unwrap();
+    assert_eq!(body["owner"], owner.to_string());
+    assert!(server.received_requests().await.unwrap().is_empty());
+
+    let err = client(&server)
+        .dry_run(|client| async move {
+            client
+                .get_margin_account_from_id(Pubkey::default(), 0)
+                .await
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ParclV3ApiClientError::InvalidInput(_)));
+}
@@ -0,0 +1,131 @@
+use parcl_v3_api_client::{
+    constants::{MAX_COMPUTE_UNIT_LIMIT, PARCL_V3_PROGRAM_ID},
+    response::{InstructionInfo, Instructions, TransactionInfo},
+};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+fn v3_ix(data: u8) -> Instruction {
+    Instruction::new_with_bytes(PARCL_V3_PROGRAM_ID, &[data], vec![])
+}
+
+#[test]
+fn merge_keeps_v3_order_and_combines_compute_budget() {
+    let deposit = Instructions {
+        v3_instructions: vec![v3_ix(1)],
+        compute_budget_instructions: vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            ComputeBudgetInstruction::set_compute_unit_price(5_000),
+        ],
+    };
+    let modify_position = Instructions {
+        v3_instructions: vec![v3_ix(2), v3_ix(3)],
+        compute_budget_instructions: vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+            ComputeBudgetInstruction::set_compute_unit_price(1_000),
+        ],
+    };
+
+    let merged = Instructions::merge(&[&deposit, &modify_position]);
+    assert_eq!(merged.v3_instructions, vec![v3_ix(1), v3_ix(2), v3_ix(3)]);
+    assert_eq!(
+        merged.compute_budget_instructions,
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(500_000),
+            ComputeBudgetInstruction::set_compute_unit_price(5_000),
+        ]
+    );
+    assert!(merged.verify_program_ids(&PARCL_V3_PROGRAM_ID).is_ok());
+
+    let merged = Instructions::merge(&[&merged, &merged, &merged]);
+    assert_eq!(
+        merged.compute_budget_instructions[0],
+        ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT)
+    );
+}
+
+#[test]
+fn merge_only_decodes_compute_budget_program_instructions() {
+    let limit = ComputeBudgetInstruction::set_compute_unit_limit(200_000);
+    let lookalike = Instruction::new_with_bytes(PARCL_V3_PROGRAM_ID, &limit.data, vec![]);
+    let set = Instructions {
+        v3_instructions: vec![],
+        compute_budget_instructions: vec![limit.clone(), lookalike.clone()],
+    };
+
+    let merged = Instructions::merge(&[&set]);
+    assert_eq!(merged.compute_budget_instructions, vec![limit, lookalike]);
+}
+
+#[test]
+fn with_recent_blockhash_replaces_blockhash() {
+    let instruction_info = InstructionInfo {
+        instructions: Instructions {
+            v3_instructions: vec![v3_ix(1)],
+            compute_budget_instructions: vec![],
+        },
+        total_required_lamports: 0,
+        required_compute_lamports: 0,
+        required_rent_lamports: 0,
+        cu_limit: 0,
+    };
+    let tx = instruction_info
+        .build_transaction(&Pubkey::new_unique(), Hash::new_unique(), &[])
+        .unwrap();
+    let transaction_info = TransactionInfo {
+        transaction: bincode::serialize(&tx).unwrap(),
+        total_required_lamports: 0,
+        required_compute_lamports: 0,
+        required_rent_lamports: 0,
+        cu_limit: 0,
+    };
+
+    let blockhash = Hash::new_unique();
+    let updated = transaction_info.with_recent_blockhash(blockhash).unwrap();
+    assert_eq!(*updated.message.recent_blockhash(), blockhash);
+    assert_eq!(updated.message.instructions(), tx.message.instructions());
+}
+
+#[test]
+fn decode_instructions_round_trips_built_transaction() {
+    let payer = Pubkey::new_unique();
+    let instructions = Instructions {
+        v3_instructions: vec![Instruction::new_with_bytes(
+            PARCL_V3_PROGRAM_ID,
+            &[7, 8],
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+                AccountMeta::new(Pubkey::new_unique(), false),
+            ],
+        )],
+        compute_budget_instructions: vec![ComputeBudgetInstruction::set_compute_unit_limit(
+            200_000,
+        )],
+    };
+    let tx = InstructionInfo {
+        instructions: instructions.clone(),
+        total_required_lamports: 0,
+        required_compute_lamports: 0,
+        required_rent_lamports: 0,
+        cu_limit: 0,
+    }
+    .build_transaction(&payer, Hash::new_unique(), &[])
+    .unwrap();
+    let transaction_info = TransactionInfo {
+        transaction: bincode::serialize(&tx).unwrap(),
+        total_required_lamports: 0,
+        required_compute_lamports: 0,
+        required_rent_lamports: 0,
+        cu_limit: 0,
+    };
+
+    assert_eq!(
+        transaction_info.decode_instructions().unwrap(),
+        instructions
+    );
+}
@@ -0,0 +1,130 @@
+use parcl_v3_api_client::response::Margins;
+use serde_json::json;
+
+fn margins(available_margin: &str, total_required_margin: &str) -> Margins {
+    serde_json::from_value(json!({
+        "available_margin": available_margin,
+        "total_required_margin": total_required_margin,
+        "required_initial_margin": "0",
+        "required_maintenance_margin": "0",
+        "required_liquidation_fee_margin": "0",
+        "accumulated_liquidation_fees": "0"
+    }))
+    .unwrap()
+}
+
+#[test]
+fn field_as_string_parses_signed_values() {
+    assert_eq!(margins("-45", "0").available_margin, -45);
+    assert_eq!(margins("+123", "+7").available_margin, 123);
+    assert_eq!(margins("+123", "+7").total_required_margin, 7);
+}
+
+#[test]
+fn field_as_string_trims_whitespace() {
+    assert_eq!(margins(" -45 ", "\t12\n").available_margin, -45);
+    assert_eq!(margins(" +45", " 12 ").total_required_margin, 12);
+}
+
+#[test]
+fn field_as_string_rejects_invalid_values() {
+    for available_margin in ["1 2", "+-5", "++5"] {
+        assert!(serde_json::from_value::<Margins>(json!({
+            "available_margin": available_margin,
+            "total_required_margin": "0",
+            "required_initial_margin": "0",
+            "required_maintenance_margin": "0",
+            "required_liquidation_fee_margin": "0",
+            "accumulated_liquidation_fees": "0"
+        }))
+        .is_err());
+    }
+}
+
+#[test]
+fn identifiers_parse_ids_and_addresses() {
+    use parcl_v3_api_client::request::MarketIdentifier;
+    use solana_sdk::pubkey::Pubkey;
+
+    let address = Pubkey::new_unique();
+    assert!(matches!("23".parse(), Ok(MarketIdentifier::Id(23))));
+    assert!(matches!(
+        address.to_string().parse(),
+        Ok(MarketIdentifier::Address(parsed)) if parsed == address
+    ));
+    assert!("not-an-id".parse::<MarketIdentifier>().is_err());
+}
+
+#[test]
+fn large_integers_round_trip_exactly() {
+    use parcl_v3_api_client::response::{PositionInfo, TransactionInfo};
+
+    let raw = r#"{"size":"-170141183460469231731687303715884105728","last_interaction_price":"340282366920938463463374607431768211454","last_interaction_funding_per_unit":"170141183460469231731687303715884105727","market_id":23}"#;
+    let position = serde_json::from_str::<PositionInfo>(raw).unwrap();
+    assert_eq!(position.size, i128::MIN);
+    assert_eq!(position.last_interaction_price, u128::MAX - 1);
+    assert_eq!(position.last_interaction_funding_per_unit, i128::MAX);
+    assert_eq!(serde_json::to_string(&position).unwrap(), raw);
+
+    let transaction_info = serde_json::from_str::<TransactionInfo>(&format!(
+        r#"{{"transaction":"","total_required_lamports":{max},"required_compute_lamports":{max},"required_rent_lamports":0,"cu_limit":0}}"#,
+        max = u64::MAX
+    ))
+    .unwrap();
+    assert_eq!(transaction_info.total_required_lamports, u64::MAX);
+}
+
+#[test]
+fn exchange_identifier_serializes_like_display() {
+    use parcl_v3_api_client::request::ExchangeIdentifier;
+    use solana_sdk::pubkey::Pubkey;
+
+    let address = Pubkey::new_unique();
+    for (identifier, expected) in [
+        (ExchangeIdentifier::Id(0), "0".to_string()),
+        (ExchangeIdentifier::Address(address), address.to_string()),
+    ] {
+        assert_eq!(serde_json::to_value(identifier).unwrap(), json!(expected));
+        assert_eq!(identifier.to_string(), expected);
+    }
+}
+
+#[test]
+fn field_as_base64_accepts_standard_and_url_safe() {
+    use parcl_v3_api_client::response::TransactionInfo;
+
+    let transaction = |encoded: &str| {
+        serde_json::from_value::<TransactionInfo>(json!({
+            "transaction": encoded,
+            "total_required_lamports": 0,
+            "required_compute_lamports": 0,
+            "required_rent_lamports": 0,
+            "cu_limit": 0
+        }))
+        .map(|transaction_info| transaction_info.transaction)
+    };
+    let bytes = vec![0xfb, 0xff, 0xbf, 0x01];
+    assert_eq!(transaction("+/+/AQ==").unwrap(), bytes);
+    assert_eq!(transaction("-_-_AQ==").unwrap(), bytes);
+    assert_eq!(transaction("-_-_AQ").unwrap(), bytes);
+    assert!(transaction("not base64!").is_err());
+}
+
+#[test]
+fn number_or_string_accepts_both_forms() {
+    use parcl_v3_api_client::response::PriceFeedInfo;
+
+    for expo in [json!(-6), json!("-6"), json!(" -6 ")] {
+        let price_feed_info =
+            serde_json::from_value::<PriceFeedInfo>(json!({ "price": "150", "expo": expo }))
+                .unwrap();
+        assert_eq!(price_feed_info.expo, -6);
+        assert_eq!(
+            serde_json::to_value(price_feed_info).unwrap(),
+            json!({ "price": "150", "expo": -6 })
+        );
+    }
+    assert!(
+        serde_json::from_value::<PriceFeedInfo>(json!({ "price": "150", "expo": "x" })).is_err()
+    );
+}